@@ -0,0 +1,413 @@
+mod rpc;
+
+use anyhow::{ anyhow, bail, Result };
+use clap::{ Parser, Subcommand, ValueEnum };
+use pumpfun_client::{
+    instructions::{ self, CreatePoolParams, MigrateKeys, TradeKeys },
+    pda,
+    quote,
+    BaseTokenConfiguration,
+    BondingCurve,
+    FeeRecipient,
+    InitializeConfiguration,
+    Pool,
+};
+use solana_sdk::{ pubkey::Pubkey, signature::{ Keypair, Signer } };
+
+use crate::rpc::Context;
+
+// create_pool and proxy_initialize chain several CPIs and run over the default 200k units
+const HEAVY_COMPUTE_UNIT_LIMIT: u32 = 600_000;
+
+#[derive(Parser)]
+#[command(name = "pumpfun", about = "Admin and operator tool for the pumpfun program")]
+struct Cli {
+    /// RPC endpoint
+    #[arg(long, short, global = true, env = "PUMPFUN_RPC_URL", default_value = "https://api.devnet.solana.com")]
+    url: String,
+
+    /// Keypair paying for and signing transactions, not read by read-only commands
+    #[arg(long, short, global = true, default_value = "~/.config/solana/id.json")]
+    keypair: String,
+
+    /// Simulate the transaction and print it with its logs instead of sending it
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Compute unit limit for the transaction, defaults to 600000 for create-pool and migrate
+    #[arg(long, global = true)]
+    compute_unit_limit: Option<u32>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Side {
+    Buy,
+    Sell,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the global configuration, the signer becomes admin and migration authority
+    Initialize {
+        #[arg(long)]
+        fee_wallet: Pubkey,
+        #[arg(long)]
+        raydium_migration_fee: u64,
+    },
+    /// Allow a new base token and set its curve parameters
+    AddBaseToken {
+        #[arg(long)]
+        base_mint: Pubkey,
+        #[arg(long)]
+        swap_fee: u64,
+        #[arg(long)]
+        bonding_curve_limitation: u64,
+        #[arg(long)]
+        initial_virtual_base_token: u64,
+        #[arg(long)]
+        initial_virtual_quote_token: u64,
+        #[arg(long)]
+        create_pool_fee_amount: u64,
+    },
+    SetSwapFee {
+        #[arg(long)]
+        base_mint: Pubkey,
+        /// In basis points, at most 10000
+        #[arg(long)]
+        swap_fee: u64,
+    },
+    SetBondingCurveLimitation {
+        #[arg(long)]
+        base_mint: Pubkey,
+        #[arg(long)]
+        limitation: u64,
+    },
+    SetCreatorFeeShare {
+        /// In basis points of the platform fee
+        #[arg(long)]
+        share: u64,
+    },
+    SetReferralFeeShare {
+        /// In basis points of the platform fee
+        #[arg(long)]
+        share: u64,
+    },
+    SetFeeRecipients {
+        /// `WALLET:WEIGHT`, repeat for each recipient
+        #[arg(long = "recipient", value_parser = parse_fee_recipient, required = true)]
+        recipients: Vec<FeeRecipient>,
+    },
+    RegisterReferrer {
+        #[arg(long)]
+        wallet: Pubkey,
+    },
+    /// Launch a token on a bonding curve, the signer is the creator
+    CreatePool {
+        #[arg(long)]
+        base_mint: Pubkey,
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        symbol: String,
+        #[arg(long)]
+        uri: String,
+        #[arg(long, default_value_t = 0)]
+        dev_buy_amount: u64,
+        /// In basis points of the dev buy output
+        #[arg(long, default_value_t = 0)]
+        initial_token_transfer_percent: u64,
+        #[arg(long)]
+        token_2022: bool,
+        /// Derive the mint from the signer and this nonce instead of a fresh keypair
+        #[arg(long)]
+        mint_nonce: Option<u64>,
+    },
+    Buy {
+        #[arg(long)]
+        mint: Pubkey,
+        /// Base token amount to spend
+        #[arg(long)]
+        amount: u64,
+        #[arg(long, default_value_t = 100)]
+        slippage_bps: u64,
+        #[arg(long)]
+        referrer: Option<Pubkey>,
+    },
+    Sell {
+        #[arg(long)]
+        mint: Pubkey,
+        /// Launched token amount to sell
+        #[arg(long)]
+        amount: u64,
+        #[arg(long, default_value_t = 100)]
+        slippage_bps: u64,
+        #[arg(long)]
+        referrer: Option<Pubkey>,
+    },
+    /// Quote a trade against the current curve state without sending anything
+    Quote {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long, value_enum)]
+        side: Side,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Print the global configuration, and a base token's configuration if given
+    ShowConfig {
+        #[arg(long)]
+        base_mint: Option<Pubkey>,
+    },
+    /// Print the pool and bonding curve of a launched token
+    ShowPool {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Move a completed curve's liquidity to Raydium CP-Swap, the signer is the migration authority
+    Migrate {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        amm_config: Pubkey,
+        /// Raydium's create pool fee receiver
+        #[arg(long)]
+        create_pool_fee: Pubkey,
+    },
+}
+
+fn parse_fee_recipient(value: &str) -> Result<FeeRecipient> {
+    let (wallet, weight) = value
+        .split_once(':')
+        .ok_or_else(|| anyhow!("expected WALLET:WEIGHT, got {value}"))?;
+    Ok(FeeRecipient {
+        wallet: wallet.parse()?,
+        weight: weight.parse()?,
+    })
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let ctx = Context::new(&cli.url, &cli.keypair, cli.dry_run, cli.compute_unit_limit);
+
+    match cli.command {
+        Command::Initialize { fee_wallet, raydium_migration_fee } => {
+            let payer = ctx.payer()?;
+            ctx.execute(&[instructions::initialize(&payer, &fee_wallet, raydium_migration_fee)], &[])
+        }
+        Command::AddBaseToken {
+            base_mint,
+            swap_fee,
+            bonding_curve_limitation,
+            initial_virtual_base_token,
+            initial_virtual_quote_token,
+            create_pool_fee_amount,
+        } => {
+            let payer = ctx.payer()?;
+            let ix = instructions::add_base_token(
+                &payer,
+                &base_mint,
+                swap_fee,
+                bonding_curve_limitation,
+                initial_virtual_base_token,
+                initial_virtual_quote_token,
+                create_pool_fee_amount
+            );
+            ctx.execute(&[ix], &[])
+        }
+        Command::SetSwapFee { base_mint, swap_fee } => {
+            let payer = ctx.payer()?;
+            ctx.execute(&[instructions::set_swap_fee(&payer, &base_mint, swap_fee)], &[])
+        }
+        Command::SetBondingCurveLimitation { base_mint, limitation } => {
+            let payer = ctx.payer()?;
+            let ix = instructions::set_bonding_curve_limitaion(&payer, &base_mint, limitation);
+            ctx.execute(&[ix], &[])
+        }
+        Command::SetCreatorFeeShare { share } => {
+            let payer = ctx.payer()?;
+            ctx.execute(&[instructions::set_creator_fee_share(&payer, share)], &[])
+        }
+        Command::SetReferralFeeShare { share } => {
+            let payer = ctx.payer()?;
+            ctx.execute(&[instructions::set_referral_fee_share(&payer, share)], &[])
+        }
+        Command::SetFeeRecipients { recipients } => {
+            let payer = ctx.payer()?;
+            ctx.execute(&[instructions::set_fee_recipients(&payer, recipients)], &[])
+        }
+        Command::RegisterReferrer { wallet } => {
+            let payer = ctx.payer()?;
+            ctx.execute(&[instructions::register_referrer(&payer, &wallet)], &[])
+        }
+        Command::CreatePool {
+            base_mint,
+            name,
+            symbol,
+            uri,
+            dev_buy_amount,
+            initial_token_transfer_percent,
+            token_2022,
+            mint_nonce,
+        } => {
+            let payer = ctx.payer()?;
+            let config: InitializeConfiguration = ctx.fetch(&pda::global_configuration())?;
+            let token_program = ctx.token_program_of(&base_mint)?;
+            let mint_keypair = Keypair::new();
+            let mint_address = match mint_nonce {
+                Some(nonce) => pda::mint(&payer, nonce),
+                None => mint_keypair.pubkey(),
+            };
+            println!("mint: {mint_address}");
+
+            let ix = instructions::create_pool(
+                &payer,
+                &mint_address,
+                &base_mint,
+                &token_program,
+                &config.fee_wallet,
+                CreatePoolParams {
+                    name,
+                    symbol,
+                    uri,
+                    dev_buy_amount,
+                    initial_token_transfer_percent,
+                    presale: None,
+                    use_token_2022: token_2022,
+                    mint_nonce,
+                }
+            );
+            let signers: &[&Keypair] = if mint_nonce.is_some() { &[] } else { &[&mint_keypair] };
+            ctx.execute_with_compute_unit_limit(HEAVY_COMPUTE_UNIT_LIMIT, &[ix], signers)
+        }
+        Command::Buy { mint, amount, slippage_bps, referrer } => {
+            let (keys, config, curve) = trade_context(&ctx, &mint, referrer)?;
+            let base_mint_fee = ctx.base_mint_fee(&keys.base_token_mint)?;
+            let quote = quote::quote_buy(&config, &curve, base_mint_fee.as_ref(), amount)?;
+            let expected_amount = quote::min_amount_out(quote.amount_out, slippage_bps);
+            ctx.execute(&[instructions::buy(&keys, amount, expected_amount)], &[])
+        }
+        Command::Sell { mint, amount, slippage_bps, referrer } => {
+            let (keys, config, curve) = trade_context(&ctx, &mint, referrer)?;
+            let base_mint_fee = ctx.base_mint_fee(&keys.base_token_mint)?;
+            let quote = quote::quote_sell(&config, &curve, base_mint_fee.as_ref(), amount)?;
+            let expected_amount = quote::min_amount_out(quote.amount_out, slippage_bps);
+            ctx.execute(&[instructions::sell(&keys, amount, expected_amount)], &[])
+        }
+        Command::Quote { mint, side, amount } => {
+            let (pool, config, curve) = curve_context(&ctx, &mint)?;
+            let base_mint_fee = ctx.base_mint_fee(&pool.base_token_mint)?;
+            let quote = match side {
+                Side::Buy => quote::quote_buy(&config, &curve, base_mint_fee.as_ref(), amount)?,
+                Side::Sell => quote::quote_sell(&config, &curve, base_mint_fee.as_ref(), amount)?,
+            };
+            println!("{quote:#?}");
+            Ok(())
+        }
+        Command::ShowConfig { base_mint } => {
+            let address = pda::global_configuration();
+            let config: InitializeConfiguration = ctx.fetch(&address)?;
+            println!("global configuration {address}");
+            println!("  admin: {}", config.admin);
+            println!("  migration authority: {}", config.migration_authority);
+            println!("  fee wallet: {}", config.fee_wallet);
+            println!("  raydium migration fee: {}", config.raydium_migration_fee);
+            println!("  creator fee share: {}", config.creator_fee_share);
+            println!("  referral fee share: {}", config.referral_fee_share);
+            for recipient in config.fee_recipients.iter().filter(|recipient| recipient.weight > 0) {
+                println!("  fee recipient: {} weight {}", recipient.wallet, recipient.weight);
+            }
+
+            if let Some(base_mint) = base_mint {
+                let address = pda::base_token_configuration(&base_mint);
+                let config: BaseTokenConfiguration = ctx.fetch(&address)?;
+                println!("base token configuration {address}");
+                println!("  base token mint: {}", config.base_token_mint);
+                println!("  swap fee: {}", config.swap_fee);
+                println!("  bonding curve limitation: {}", config.bonding_curve_limitation);
+                println!("  initial virtual base token: {}", config.initial_virtual_base_token);
+                println!("  initial virtual quote token: {}", config.initial_virtual_quote_token);
+                println!("  create pool fee amount: {}", config.create_pool_fee_amount);
+            }
+            Ok(())
+        }
+        Command::ShowPool { mint } => {
+            let pool_address = pda::pool(&mint);
+            let pool: Pool = ctx.fetch(&pool_address)?;
+            let curve_address = pda::bonding_curve(&mint);
+            let curve: BondingCurve = ctx.fetch(&curve_address)?;
+
+            println!("pool {pool_address}");
+            println!("  creator: {}", pool.creator);
+            println!("  mint: {}", pool.mint_address);
+            println!("  base token mint: {}", pool.base_token_mint);
+            println!("  created at: {} (slot {})", pool.created_at, pool.created_slot);
+            println!("  buy volume: {}", pool.buy_volume);
+            println!("  sell volume: {}", pool.sell_volume);
+            println!("  trade count: {}", pool.trade_count);
+            println!("  total fees: {}", pool.total_fees);
+            if pool.migrated_pool != Pubkey::default() {
+                println!("  migrated to: {} at {}", pool.migrated_pool, pool.migrated_at);
+            }
+            println!("bonding curve {curve_address}");
+            println!("  virtual base: {}", curve.init_virtual_base_token);
+            println!("  virtual quote: {}", curve.init_virtual_quote_token);
+            println!("  base reserves: {}", curve.base_token_reserves);
+            println!("  quote reserves: {}", curve.quote_token_reserves);
+            println!("  price (x32): {}", quote::spot_price_x32(&curve)?);
+            println!("  completed: {}", curve.is_completed);
+            Ok(())
+        }
+        Command::Migrate { mint, amm_config, create_pool_fee } => {
+            let payer = ctx.payer()?;
+            let pool: Pool = ctx.fetch(&pda::pool(&mint))?;
+            let curve: BondingCurve = ctx.fetch(&pda::bonding_curve(&mint))?;
+            if !curve.is_completed {
+                bail!("bonding curve of {mint} is not completed yet");
+            }
+
+            let keys = MigrateKeys {
+                migration_authority: payer,
+                mint_address: mint,
+                base_token_mint: pool.base_token_mint,
+                token_program: ctx.token_program_of(&pool.base_token_mint)?,
+                quote_token_program: ctx.token_program_of(&mint)?,
+                amm_config,
+                create_pool_fee,
+            };
+            ctx.execute_with_compute_unit_limit(
+                HEAVY_COMPUTE_UNIT_LIMIT,
+                &[instructions::proxy_initialize(&keys)],
+                &[]
+            )
+        }
+    }
+}
+
+fn curve_context(ctx: &Context, mint: &Pubkey) -> Result<(Pool, BaseTokenConfiguration, BondingCurve)> {
+    let pool: Pool = ctx.fetch(&pda::pool(mint))?;
+    let config: BaseTokenConfiguration = ctx.fetch(
+        &pda::base_token_configuration(&pool.base_token_mint)
+    )?;
+    let curve: BondingCurve = ctx.fetch(&pda::bonding_curve(mint))?;
+    Ok((pool, config, curve))
+}
+
+fn trade_context(
+    ctx: &Context,
+    mint: &Pubkey,
+    referrer: Option<Pubkey>
+) -> Result<(TradeKeys, BaseTokenConfiguration, BondingCurve)> {
+    let (pool, config, curve) = curve_context(ctx, mint)?;
+    let keys = TradeKeys {
+        payer: ctx.payer()?,
+        mint_address: *mint,
+        base_token_mint: pool.base_token_mint,
+        token_program: ctx.token_program_of(&pool.base_token_mint)?,
+        quote_token_program: ctx.token_program_of(mint)?,
+        referrer,
+    };
+    Ok((keys, config, curve))
+}
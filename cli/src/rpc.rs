@@ -0,0 +1,140 @@
+use std::cell::OnceCell;
+
+use anchor_lang::AccountDeserialize;
+use anyhow::{ anyhow, Context as _, Result };
+use pumpfun_client::quote::BaseMintFee;
+use solana_client::{ rpc_client::RpcClient, rpc_config::RpcSimulateTransactionConfig };
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{ read_keypair_file, Keypair, Signer },
+    transaction::Transaction,
+};
+
+pub struct Context {
+    pub rpc: RpcClient,
+    pub dry_run: bool,
+    /// Overrides the compute unit limit of every transaction
+    pub compute_unit_limit: Option<u32>,
+    keypair_path: String,
+    // Read on first use, so read-only commands work without a keypair
+    payer: OnceCell<Keypair>,
+}
+
+impl Context {
+    pub fn new(url: &str, keypair_path: &str, dry_run: bool, compute_unit_limit: Option<u32>) -> Self {
+        Self {
+            rpc: RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed()),
+            dry_run,
+            compute_unit_limit,
+            keypair_path: keypair_path.to_string(),
+            payer: OnceCell::new(),
+        }
+    }
+
+    fn signer(&self) -> Result<&Keypair> {
+        if let Some(payer) = self.payer.get() {
+            return Ok(payer);
+        }
+        let path = shellexpand::tilde(&self.keypair_path).into_owned();
+        let payer = read_keypair_file(&path).map_err(|err|
+            anyhow!("failed to read keypair {path}: {err}")
+        )?;
+        Ok(self.payer.get_or_init(|| payer))
+    }
+
+    pub fn payer(&self) -> Result<Pubkey> {
+        Ok(self.signer()?.pubkey())
+    }
+
+    pub fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        let data = self.rpc
+            .get_account_data(address)
+            .with_context(|| format!("failed to fetch account {address}"))?;
+        pumpfun_client::accounts::decode(&data).map_err(|err| anyhow!("failed to decode account {address}: {err}"))
+    }
+
+    /// Token program owning a mint, so callers don't have to pass it
+    pub fn token_program_of(&self, mint: &Pubkey) -> Result<Pubkey> {
+        let account = self.rpc
+            .get_account(mint)
+            .with_context(|| format!("failed to fetch mint {mint}"))?;
+        Ok(account.owner)
+    }
+
+    /// Transfer fee the base mint withholds in the current epoch
+    pub fn base_mint_fee(&self, mint: &Pubkey) -> Result<Option<BaseMintFee>> {
+        let data = self.rpc
+            .get_account_data(mint)
+            .with_context(|| format!("failed to fetch mint {mint}"))?;
+        let epoch = self.rpc.get_epoch_info()?.epoch;
+        BaseMintFee::from_mint_data(&data, epoch).map_err(|err| anyhow!("failed to decode mint {mint}: {err}"))
+    }
+
+    /// Signs with the payer plus `signers`, then either simulates or sends the transaction
+    pub fn execute(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<()> {
+        self.send(self.compute_unit_limit, instructions, signers)
+    }
+
+    /// Like `execute`, for instructions that need more than the default 200k compute units
+    pub fn execute_with_compute_unit_limit(
+        &self,
+        compute_unit_limit: u32,
+        instructions: &[Instruction],
+        signers: &[&Keypair]
+    ) -> Result<()> {
+        self.send(Some(self.compute_unit_limit.unwrap_or(compute_unit_limit)), instructions, signers)
+    }
+
+    fn send(
+        &self,
+        compute_unit_limit: Option<u32>,
+        instructions: &[Instruction],
+        signers: &[&Keypair]
+    ) -> Result<()> {
+        let instructions: Vec<Instruction> = compute_unit_limit
+            .map(ComputeBudgetInstruction::set_compute_unit_limit)
+            .into_iter()
+            .chain(instructions.iter().cloned())
+            .collect();
+        let payer = self.signer()?;
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let mut all_signers: Vec<&Keypair> = vec![payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            blockhash
+        );
+
+        if !self.dry_run {
+            let signature = self.rpc.send_and_confirm_transaction(&tx)?;
+            println!("signature: {signature}");
+            return Ok(());
+        }
+
+        println!("transaction: {:#?}", tx.message);
+        let result = self.rpc.simulate_transaction_with_config(&tx, RpcSimulateTransactionConfig {
+            sig_verify: true,
+            commitment: Some(self.rpc.commitment()),
+            ..RpcSimulateTransactionConfig::default()
+        })?.value;
+
+        for log in result.logs.unwrap_or_default() {
+            println!("  {log}");
+        }
+        if let Some(units) = result.units_consumed {
+            println!("compute units: {units}");
+        }
+        match result.err {
+            Some(err) => Err(anyhow!("simulation failed: {err}")),
+            None => {
+                println!("simulation succeeded");
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use anchor_lang::{ error::ErrorCode, AccountDeserialize, Discriminator, Result };
+use pumpfun_smart_contract::state::{
+    BaseTokenConfiguration,
+    BondingCurve,
+    InitializeConfiguration,
+    ObservationState,
+    Pool,
+    PresaleReceipt,
+    Referrer,
+};
+
+/// Decodes any of the program's accounts, checking the discriminator
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_global_configuration(data: &[u8]) -> Result<InitializeConfiguration> {
+    decode(data)
+}
+
+pub fn decode_base_token_configuration(data: &[u8]) -> Result<BaseTokenConfiguration> {
+    decode(data)
+}
+
+pub fn decode_bonding_curve(data: &[u8]) -> Result<BondingCurve> {
+    decode(data)
+}
+
+pub fn decode_pool(data: &[u8]) -> Result<Pool> {
+    decode(data)
+}
+
+pub fn decode_presale_receipt(data: &[u8]) -> Result<PresaleReceipt> {
+    decode(data)
+}
+
+pub fn decode_referrer(data: &[u8]) -> Result<Referrer> {
+    decode(data)
+}
+
+// Zero-copy and packed, so it's read as raw bytes instead of borsh
+pub fn decode_observation_state(data: &[u8]) -> Result<ObservationState> {
+    let discriminator = ObservationState::DISCRIMINATOR;
+    if data.len() < discriminator.len() {
+        return Err(ErrorCode::AccountDiscriminatorNotFound.into());
+    }
+    if &data[..discriminator.len()] != discriminator {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+
+    let body = &data[discriminator.len()..];
+    let size = std::mem::size_of::<ObservationState>();
+    if body.len() < size {
+        return Err(ErrorCode::AccountDidNotDeserialize.into());
+    }
+    Ok(bytemuck::pod_read_unaligned(&body[..size]))
+}
@@ -0,0 +1,598 @@
+//! One builder per program instruction. Builders derive every PDA and ATA themselves, callers
+//! only pass the wallets, mints and token programs involved.
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{ instruction::{ AccountMeta, Instruction }, system_program, sysvar },
+    InstructionData,
+    ToAccountMetas,
+};
+use anchor_spl::{ associated_token, metadata::mpl_token_metadata, token, token_2022 };
+use pumpfun_smart_contract::{
+    accounts,
+    consts::CP_SWAP_PROGRAM_ID,
+    instruction,
+    state::{ FeeRecipient, PresaleConfig },
+};
+
+use crate::{ pda, PROGRAM_ID };
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Accounts shared by `buy`, `presale_buy` and `sell`
+#[derive(Clone, Copy, Debug)]
+pub struct TradeKeys {
+    pub payer: Pubkey,
+    pub mint_address: Pubkey,
+    pub base_token_mint: Pubkey,
+    /// Token program of the base mint
+    pub token_program: Pubkey,
+    /// Token program of the launched mint
+    pub quote_token_program: Pubkey,
+    /// Wallet of a registered referrer, if any
+    pub referrer: Option<Pubkey>,
+}
+
+pub struct CreatePoolParams {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub dev_buy_amount: u64,
+    pub initial_token_transfer_percent: u64,
+    pub presale: Option<PresaleConfig>,
+    pub use_token_2022: bool,
+    /// Derive the mint from the payer and this nonce instead of a fresh keypair
+    pub mint_nonce: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MigrateKeys {
+    pub migration_authority: Pubkey,
+    pub mint_address: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub token_program: Pubkey,
+    pub quote_token_program: Pubkey,
+    pub amm_config: Pubkey,
+    /// Raydium's create pool fee receiver
+    pub create_pool_fee: Pubkey,
+}
+
+pub fn initialize(admin: &Pubkey, fee_wallet: &Pubkey, raydium_migration_fee: u64) -> Instruction {
+    build(
+        accounts::Initialize {
+            global_configuration: pda::global_configuration(),
+            fee_account: *fee_wallet,
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        instruction::Initialize {
+            fee_wallet: *fee_wallet,
+            raydium_migration_fee,
+        }
+    )
+}
+
+pub fn add_base_token(
+    admin: &Pubkey,
+    base_token_mint: &Pubkey,
+    swap_fee: u64,
+    bonding_curve_limitation: u64,
+    initial_virtual_base_token: u64,
+    initial_virtual_quote_token: u64,
+    create_pool_fee_amount: u64
+) -> Instruction {
+    build(
+        accounts::AddBaseToken {
+            global_configuration: pda::global_configuration(),
+            base_token_configuration: pda::base_token_configuration(base_token_mint),
+            base_token_mint: *base_token_mint,
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        instruction::AddBaseToken {
+            swap_fee,
+            bonding_curve_limitation,
+            initial_virtual_base_token,
+            initial_virtual_quote_token,
+            create_pool_fee_amount,
+        }
+    )
+}
+
+/// `mint_address` must be `pda::mint(payer, nonce)` when `params.mint_nonce` is set, otherwise
+/// a fresh keypair that also signs the transaction
+pub fn create_pool(
+    payer: &Pubkey,
+    mint_address: &Pubkey,
+    base_token_mint: &Pubkey,
+    token_program: &Pubkey,
+    fee_account: &Pubkey,
+    params: CreatePoolParams
+) -> Instruction {
+    let quote_token_program = if params.use_token_2022 { token_2022::ID } else { token::ID };
+    let pool = pda::pool(mint_address);
+    let protocol_fee_vault = pda::protocol_fee_vault(base_token_mint);
+    let creator_vault = pda::creator_vault(mint_address);
+    let mint_is_keypair = params.mint_nonce.is_none();
+
+    let mut ix = build(
+        accounts::CreatePool {
+            global_configuration: pda::global_configuration(),
+            base_token_configuration: pda::base_token_configuration(base_token_mint),
+            bonding_curve: pda::bonding_curve(mint_address),
+            price_observation: pda::price_observation(mint_address),
+            mint_address: *mint_address,
+            base_token_mint: *base_token_mint,
+            user_base_token_ata: pda::associated_token_address(payer, base_token_mint, token_program),
+            user_quote_token_ata: pda::associated_token_address(
+                payer,
+                mint_address,
+                &quote_token_program
+            ),
+            pool,
+            base_token_pool: pda::associated_token_address(&pool, base_token_mint, token_program),
+            quote_token_pool: pda::associated_token_address(
+                &pool,
+                mint_address,
+                &quote_token_program
+            ),
+            metadata: (!params.use_token_2022).then(|| pda::metadata(mint_address)),
+            fee_account: *fee_account,
+            protocol_fee_vault,
+            protocol_fee_vault_ata: pda::associated_token_address(
+                &protocol_fee_vault,
+                base_token_mint,
+                token_program
+            ),
+            fee_quote_token_ata: pda::associated_token_address(
+                fee_account,
+                mint_address,
+                &quote_token_program
+            ),
+            creator_vault,
+            creator_vault_base_token_ata: pda::associated_token_address(
+                &creator_vault,
+                base_token_mint,
+                token_program
+            ),
+            payer: *payer,
+            token_metadata_program: (!params.use_token_2022).then_some(mpl_token_metadata::ID),
+            associated_token_program: associated_token::ID,
+            token_program: *token_program,
+            quote_token_program,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            event_authority: pda::event_authority(),
+            program: PROGRAM_ID,
+        },
+        instruction::CreatePool {
+            name: params.name,
+            symbol: params.symbol,
+            uri: params.uri,
+            dev_buy_amount: params.dev_buy_amount,
+            initial_token_transfer_percent: params.initial_token_transfer_percent,
+            presale: params.presale,
+            use_token_2022: params.use_token_2022,
+            mint_nonce: params.mint_nonce,
+        }
+    );
+    if mint_is_keypair {
+        for meta in ix.accounts.iter_mut().filter(|meta| meta.pubkey == *mint_address) {
+            meta.is_signer = true;
+        }
+    }
+    ix
+}
+
+fn buy_accounts(keys: &TradeKeys) -> accounts::Buy {
+    let pool = pda::pool(&keys.mint_address);
+    let protocol_fee_vault = pda::protocol_fee_vault(&keys.base_token_mint);
+    let creator_vault = pda::creator_vault(&keys.mint_address);
+
+    accounts::Buy {
+        global_configuration: pda::global_configuration(),
+        base_token_configuration: pda::base_token_configuration(&keys.base_token_mint),
+        bonding_curve: pda::bonding_curve(&keys.mint_address),
+        price_observation: pda::price_observation(&keys.mint_address),
+        mint_address: keys.mint_address,
+        base_token_mint: keys.base_token_mint,
+        user_base_token_ata: pda::associated_token_address(
+            &keys.payer,
+            &keys.base_token_mint,
+            &keys.token_program
+        ),
+        user_quote_token_ata: pda::associated_token_address(
+            &keys.payer,
+            &keys.mint_address,
+            &keys.quote_token_program
+        ),
+        pool,
+        base_token_pool: pda::associated_token_address(
+            &pool,
+            &keys.base_token_mint,
+            &keys.token_program
+        ),
+        quote_token_pool: pda::associated_token_address(
+            &pool,
+            &keys.mint_address,
+            &keys.quote_token_program
+        ),
+        protocol_fee_vault,
+        protocol_fee_vault_ata: pda::associated_token_address(
+            &protocol_fee_vault,
+            &keys.base_token_mint,
+            &keys.token_program
+        ),
+        creator_vault,
+        creator_vault_base_token_ata: pda::associated_token_address(
+            &creator_vault,
+            &keys.base_token_mint,
+            &keys.token_program
+        ),
+        referrer: keys.referrer.as_ref().map(pda::referrer),
+        referrer_base_token_ata: keys.referrer
+            .as_ref()
+            .map(|wallet| {
+                pda::associated_token_address(wallet, &keys.base_token_mint, &keys.token_program)
+            }),
+        payer: keys.payer,
+        associated_token_program: associated_token::ID,
+        token_program: keys.token_program,
+        quote_token_program: keys.quote_token_program,
+        system_program: system_program::ID,
+        event_authority: pda::event_authority(),
+        program: PROGRAM_ID,
+    }
+}
+
+pub fn buy(keys: &TradeKeys, base_input_amount: u64, expected_amount: u64) -> Instruction {
+    build(buy_accounts(keys), instruction::Buy {
+        base_input_amount,
+        expected_amount,
+    })
+}
+
+pub fn presale_buy(
+    keys: &TradeKeys,
+    base_input_amount: u64,
+    expected_amount: u64,
+    allocation: u64,
+    proof: Vec<[u8; 32]>
+) -> Instruction {
+    build(
+        accounts::PresaleBuy {
+            buy: buy_accounts(keys),
+            presale_receipt: pda::presale_receipt(&keys.mint_address, &keys.payer),
+            system_program: system_program::ID,
+        },
+        instruction::PresaleBuy {
+            base_input_amount,
+            expected_amount,
+            allocation,
+            proof,
+        }
+    )
+}
+
+pub fn sell(keys: &TradeKeys, quote_input_amount: u64, expected_amount: u64) -> Instruction {
+    let buy = buy_accounts(keys);
+    build(
+        accounts::Sell {
+            global_configuration: buy.global_configuration,
+            base_token_configuration: buy.base_token_configuration,
+            bonding_curve: buy.bonding_curve,
+            price_observation: buy.price_observation,
+            mint_address: buy.mint_address,
+            base_token_mint: buy.base_token_mint,
+            user_base_token_ata: buy.user_base_token_ata,
+            user_quote_token_ata: buy.user_quote_token_ata,
+            pool: buy.pool,
+            base_token_pool: buy.base_token_pool,
+            quote_token_pool: buy.quote_token_pool,
+            protocol_fee_vault: buy.protocol_fee_vault,
+            protocol_fee_vault_ata: buy.protocol_fee_vault_ata,
+            creator_vault: buy.creator_vault,
+            creator_vault_base_token_ata: buy.creator_vault_base_token_ata,
+            referrer: buy.referrer,
+            referrer_base_token_ata: buy.referrer_base_token_ata,
+            payer: buy.payer,
+            associated_token_program: buy.associated_token_program,
+            token_program: buy.token_program,
+            quote_token_program: buy.quote_token_program,
+            system_program: buy.system_program,
+            event_authority: buy.event_authority,
+            program: buy.program,
+        },
+        instruction::Sell {
+            quote_input_amount,
+            expected_amount,
+        }
+    )
+}
+
+// Raydium CP-Swap seeds, see raydium_cp_swap::states
+fn cp_swap_pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &CP_SWAP_PROGRAM_ID).0
+}
+
+pub fn proxy_initialize(keys: &MigrateKeys) -> Instruction {
+    let (token_0_mint, token_0_program, token_1_mint, token_1_program) = if
+        keys.base_token_mint < keys.mint_address
+    {
+        (keys.base_token_mint, keys.token_program, keys.mint_address, keys.quote_token_program)
+    } else {
+        (keys.mint_address, keys.quote_token_program, keys.base_token_mint, keys.token_program)
+    };
+
+    let pool_state = cp_swap_pda(
+        &[b"pool", keys.amm_config.as_ref(), token_0_mint.as_ref(), token_1_mint.as_ref()]
+    );
+    let lp_mint = cp_swap_pda(&[b"pool_lp_mint", pool_state.as_ref()]);
+    let is_spl_token_mint = keys.quote_token_program == token::ID;
+    let pool = pda::pool(&keys.mint_address);
+    let liquidity_owner = pda::liquidity_owner(&keys.mint_address);
+
+    build(
+        accounts::ProxyInitialize {
+            global_configuration: pda::global_configuration(),
+            bonding_curve: pda::bonding_curve(&keys.mint_address),
+            mint_address: keys.mint_address,
+            pool,
+            base_token_pool: pda::associated_token_address(
+                &pool,
+                &keys.base_token_mint,
+                &keys.token_program
+            ),
+            quote_token_pool: pda::associated_token_address(
+                &pool,
+                &keys.mint_address,
+                &keys.quote_token_program
+            ),
+            migration_authority: keys.migration_authority,
+            cp_swap_program: CP_SWAP_PROGRAM_ID,
+            liquidity_owner,
+            amm_config: keys.amm_config,
+            authority: cp_swap_pda(&[b"vault_and_lp_mint_auth_seed"]),
+            pool_state,
+            token_0_mint,
+            token_1_mint,
+            lp_mint,
+            creator_token_0: pda::associated_token_address(
+                &liquidity_owner,
+                &token_0_mint,
+                &token_0_program
+            ),
+            creator_token_1: pda::associated_token_address(
+                &liquidity_owner,
+                &token_1_mint,
+                &token_1_program
+            ),
+            creator_lp_token: pda::associated_token_address(&liquidity_owner, &lp_mint, &token::ID),
+            token_0_vault: cp_swap_pda(&[b"pool_vault", pool_state.as_ref(), token_0_mint.as_ref()]),
+            token_1_vault: cp_swap_pda(&[b"pool_vault", pool_state.as_ref(), token_1_mint.as_ref()]),
+            create_pool_fee: keys.create_pool_fee,
+            observation_state: cp_swap_pda(&[b"observation", pool_state.as_ref()]),
+            token_program: token::ID,
+            token_0_program,
+            token_1_program,
+            associated_token_program: associated_token::ID,
+            metadata: is_spl_token_mint.then(|| pda::metadata(&keys.mint_address)),
+            token_metadata_program: is_spl_token_mint.then_some(mpl_token_metadata::ID),
+            quote_token_program: keys.quote_token_program,
+            rent: sysvar::rent::ID,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: PROGRAM_ID,
+        },
+        instruction::ProxyInitialize {}
+    )
+}
+
+pub fn claim_creator_fees(
+    creator: &Pubkey,
+    mint_address: &Pubkey,
+    base_token_mint: &Pubkey,
+    token_program: &Pubkey
+) -> Instruction {
+    let creator_vault = pda::creator_vault(mint_address);
+    build(
+        accounts::ClaimCreatorFees {
+            pool: pda::pool(mint_address),
+            mint_address: *mint_address,
+            base_token_mint: *base_token_mint,
+            creator_vault,
+            creator_vault_base_token_ata: pda::associated_token_address(
+                &creator_vault,
+                base_token_mint,
+                token_program
+            ),
+            creator_base_token_ata: pda::associated_token_address(
+                creator,
+                base_token_mint,
+                token_program
+            ),
+            creator: *creator,
+            associated_token_program: associated_token::ID,
+            token_program: *token_program,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: PROGRAM_ID,
+        },
+        instruction::ClaimCreatorFees {}
+    )
+}
+
+pub fn register_referrer(admin: &Pubkey, wallet: &Pubkey) -> Instruction {
+    build(
+        accounts::RegisterReferrer {
+            global_configuration: pda::global_configuration(),
+            referrer: pda::referrer(wallet),
+            wallet: *wallet,
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterReferrer {}
+    )
+}
+
+/// `recipients` are the wallets of the configured fee recipients with a non-zero weight,
+/// in the order they are stored in the global configuration
+pub fn withdraw_protocol_fees(
+    admin: &Pubkey,
+    base_token_mint: &Pubkey,
+    token_program: &Pubkey,
+    recipients: &[Pubkey]
+) -> Instruction {
+    let protocol_fee_vault = pda::protocol_fee_vault(base_token_mint);
+    let mut ix = build(
+        accounts::WithdrawProtocolFees {
+            global_configuration: pda::global_configuration(),
+            base_token_mint: *base_token_mint,
+            protocol_fee_vault,
+            protocol_fee_vault_ata: pda::associated_token_address(
+                &protocol_fee_vault,
+                base_token_mint,
+                token_program
+            ),
+            admin: *admin,
+            associated_token_program: associated_token::ID,
+            token_program: *token_program,
+            event_authority: pda::event_authority(),
+            program: PROGRAM_ID,
+        },
+        instruction::WithdrawProtocolFees {}
+    );
+    ix.accounts.extend(
+        recipients.iter().map(|wallet| {
+            AccountMeta::new(
+                pda::associated_token_address(wallet, base_token_mint, token_program),
+                false
+            )
+        })
+    );
+    ix
+}
+
+pub fn update_token_metadata(
+    creator: &Pubkey,
+    mint_address: &Pubkey,
+    quote_token_program: &Pubkey,
+    name: String,
+    symbol: String,
+    uri: String
+) -> Instruction {
+    let is_spl_token_mint = *quote_token_program == token::ID;
+    build(
+        accounts::UpdateTokenMetadata {
+            bonding_curve: pda::bonding_curve(mint_address),
+            mint_address: *mint_address,
+            pool: pda::pool(mint_address),
+            metadata: is_spl_token_mint.then(|| pda::metadata(mint_address)),
+            creator: *creator,
+            token_metadata_program: is_spl_token_mint.then_some(mpl_token_metadata::ID),
+            quote_token_program: *quote_token_program,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: PROGRAM_ID,
+        },
+        instruction::UpdateTokenMetadata { name, symbol, uri }
+    )
+}
+
+pub fn get_twap(mint_address: &Pubkey, window: u64) -> Instruction {
+    build(
+        accounts::GetTwap {
+            bonding_curve: pda::bonding_curve(mint_address),
+            price_observation: pda::price_observation(mint_address),
+            mint_address: *mint_address,
+        },
+        instruction::GetTwap { window }
+    )
+}
+
+fn quote_accounts(mint_address: &Pubkey, base_token_mint: &Pubkey) -> accounts::Quote {
+    accounts::Quote {
+        base_token_configuration: pda::base_token_configuration(base_token_mint),
+        bonding_curve: pda::bonding_curve(mint_address),
+        pool: pda::pool(mint_address),
+        mint_address: *mint_address,
+        base_token_mint: *base_token_mint,
+    }
+}
+
+pub fn quote_buy(mint_address: &Pubkey, base_token_mint: &Pubkey, base_input_amount: u64) -> Instruction {
+    build(quote_accounts(mint_address, base_token_mint), instruction::QuoteBuy {
+        base_input_amount,
+    })
+}
+
+pub fn quote_sell(
+    mint_address: &Pubkey,
+    base_token_mint: &Pubkey,
+    quote_input_amount: u64
+) -> Instruction {
+    build(quote_accounts(mint_address, base_token_mint), instruction::QuoteSell {
+        quote_input_amount,
+    })
+}
+
+fn set_base_token_configuration_accounts(
+    admin: &Pubkey,
+    base_token_mint: &Pubkey
+) -> accounts::SetBaseTokenConfiguration {
+    accounts::SetBaseTokenConfiguration {
+        admin: *admin,
+        global_configuration: pda::global_configuration(),
+        base_token_configuration: pda::base_token_configuration(base_token_mint),
+    }
+}
+
+fn set_global_configuration_accounts(admin: &Pubkey) -> accounts::SetGlobalConfiguration {
+    accounts::SetGlobalConfiguration {
+        admin: *admin,
+        global_configuration: pda::global_configuration(),
+    }
+}
+
+pub fn set_swap_fee(admin: &Pubkey, base_token_mint: &Pubkey, new_swap_fee: u64) -> Instruction {
+    build(set_base_token_configuration_accounts(admin, base_token_mint), instruction::SetSwapFee {
+        new_swap_fee,
+    })
+}
+
+pub fn set_bonding_curve_limitaion(
+    admin: &Pubkey,
+    base_token_mint: &Pubkey,
+    new_bonding_curve_limitaion: u64
+) -> Instruction {
+    build(
+        set_base_token_configuration_accounts(admin, base_token_mint),
+        instruction::SetBondingCurveLimitaion {
+            new_bonding_curve_limitaion,
+        }
+    )
+}
+
+pub fn set_creator_fee_share(admin: &Pubkey, new_creator_fee_share: u64) -> Instruction {
+    build(set_global_configuration_accounts(admin), instruction::SetCreatorFeeShare {
+        new_creator_fee_share,
+    })
+}
+
+pub fn set_referral_fee_share(admin: &Pubkey, new_referral_fee_share: u64) -> Instruction {
+    build(set_global_configuration_accounts(admin), instruction::SetReferralFeeShare {
+        new_referral_fee_share,
+    })
+}
+
+pub fn set_fee_recipients(admin: &Pubkey, new_fee_recipients: Vec<FeeRecipient>) -> Instruction {
+    build(set_global_configuration_accounts(admin), instruction::SetFeeRecipients {
+        new_fee_recipients,
+    })
+}
@@ -0,0 +1,21 @@
+//! Off-chain helpers for the pumpfun program: PDA derivation, instruction builders,
+//! account decoders and quotes that reuse the on-chain curve math.
+
+pub mod accounts;
+pub mod instructions;
+pub mod pda;
+pub mod quote;
+
+pub use pumpfun_smart_contract::ID as PROGRAM_ID;
+pub use pumpfun_smart_contract::state::{
+    BaseTokenConfiguration,
+    BondingCurve,
+    FeeRecipient,
+    InitializeConfiguration,
+    ObservationState,
+    Pool,
+    PresaleConfig,
+    PresaleReceipt,
+    Referrer,
+    SwapQuote,
+};
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    metadata::mpl_token_metadata,
+};
+use pumpfun_smart_contract::consts::*;
+
+use crate::PROGRAM_ID;
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &PROGRAM_ID).0
+}
+
+pub fn global_configuration() -> Pubkey {
+    find(&[CONFIG_SEED.as_bytes()])
+}
+
+pub fn base_token_configuration(base_token_mint: &Pubkey) -> Pubkey {
+    find(&[BASE_TOKEN_CONFIG_SEED.as_bytes(), base_token_mint.as_ref()])
+}
+
+pub fn bonding_curve(mint_address: &Pubkey) -> Pubkey {
+    find(&[mint_address.as_ref(), CURVE_SEED.as_bytes()])
+}
+
+pub fn price_observation(mint_address: &Pubkey) -> Pubkey {
+    find(&[mint_address.as_ref(), OBSERVATION_SEED.as_bytes()])
+}
+
+/// Pool account, also the authority of the curve's token accounts
+pub fn pool(mint_address: &Pubkey) -> Pubkey {
+    find(&[mint_address.as_ref(), POOL_SEED.as_bytes()])
+}
+
+/// Creates the Raydium pool on migration and owns its liquidity
+pub fn liquidity_owner(mint_address: &Pubkey) -> Pubkey {
+    find(&[mint_address.as_ref(), LIQUIDITY_OWNER_SEED.as_bytes()])
+}
+
+pub fn creator_vault(mint_address: &Pubkey) -> Pubkey {
+    find(&[mint_address.as_ref(), CREATOR_VAULT_SEED.as_bytes()])
+}
+
+pub fn protocol_fee_vault(base_token_mint: &Pubkey) -> Pubkey {
+    find(&[PROTOCOL_FEE_VAULT_SEED.as_bytes(), base_token_mint.as_ref()])
+}
+
+pub fn presale_receipt(mint_address: &Pubkey, user: &Pubkey) -> Pubkey {
+    find(&[mint_address.as_ref(), PRESALE_SEED.as_bytes(), user.as_ref()])
+}
+
+pub fn referrer(wallet: &Pubkey) -> Pubkey {
+    find(&[REFERRER_SEED.as_bytes(), wallet.as_ref()])
+}
+
+/// Mint created by `create_pool` when a `mint_nonce` is passed instead of a fresh keypair
+pub fn mint(payer: &Pubkey, mint_nonce: u64) -> Pubkey {
+    find(&[MINT_SEED.as_bytes(), payer.as_ref(), &mint_nonce.to_le_bytes()])
+}
+
+pub fn event_authority() -> Pubkey {
+    find(&[EVENT_AUTHORITY_SEED.as_bytes()])
+}
+
+/// Metaplex metadata account, only used by spl-token mints
+pub fn metadata(mint_address: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", mpl_token_metadata::ID.as_ref(), mint_address.as_ref()],
+        &mpl_token_metadata::ID
+    ).0
+}
+
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
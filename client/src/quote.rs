@@ -0,0 +1,173 @@
+//! Quotes call straight into the program's `BondingCurve` methods so they can't drift from
+//! what `buy` and `sell` execute, including the Token-2022 transfer fee on the base mint.
+
+use anchor_lang::Result;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{ transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions },
+    state::Mint,
+};
+use pumpfun_smart_contract::{
+    errors::CustomError,
+    state::{ BaseTokenConfiguration, BondingCurve, SwapQuote },
+};
+
+/// Token-2022 transfer fee withheld by the base mint, as of the epoch the trade lands in
+#[derive(Clone, Copy, Debug)]
+pub struct BaseMintFee {
+    pub config: TransferFeeConfig,
+    pub epoch: u64,
+}
+
+impl BaseMintFee {
+    /// Reads the fee from the base mint's account data, `None` when the mint charges none
+    pub fn from_mint_data(data: &[u8], epoch: u64) -> Result<Option<Self>> {
+        let mint = StateWithExtensions::<Mint>::unpack(data)?;
+        Ok(mint.get_extension::<TransferFeeConfig>().ok().map(|config| Self { config: *config, epoch }))
+    }
+
+    fn on(&self, amount: u64) -> Result<u64> {
+        Ok(self.config.calculate_epoch_fee(self.epoch, amount).ok_or(CustomError::MathOverflow)?)
+    }
+}
+
+fn transfer_fee(base_mint_fee: Option<&BaseMintFee>, amount: u64) -> Result<u64> {
+    base_mint_fee.map_or(Ok(0), |fee| fee.on(amount))
+}
+
+pub fn quote_buy(
+    config: &BaseTokenConfiguration,
+    bonding_curve: &BondingCurve,
+    base_mint_fee: Option<&BaseMintFee>,
+    base_input_amount: u64
+) -> Result<SwapQuote> {
+    bonding_curve.quote_buy(config, base_input_amount, |amount| transfer_fee(base_mint_fee, amount))
+}
+
+pub fn quote_sell(
+    config: &BaseTokenConfiguration,
+    bonding_curve: &BondingCurve,
+    base_mint_fee: Option<&BaseMintFee>,
+    quote_input_amount: u64
+) -> Result<SwapQuote> {
+    bonding_curve.quote_sell(config, quote_input_amount, |amount| transfer_fee(base_mint_fee, amount))
+}
+
+/// Spot price in base token per quote token, as a Q32 fixed point number
+pub fn spot_price_x32(bonding_curve: &BondingCurve) -> Result<u128> {
+    bonding_curve.price_x32()
+}
+
+/// Lowest acceptable output for `expected_amount` given a slippage tolerance in basis points
+pub fn min_amount_out(amount_out: u64, slippage_bps: u64) -> u64 {
+    let slippage_bps = slippage_bps.min(10000) as u128;
+    (((amount_out as u128) * (10000 - slippage_bps)) / 10000) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFee;
+
+    const SWAP_FEE: u64 = 100;
+
+    fn config() -> BaseTokenConfiguration {
+        BaseTokenConfiguration {
+            base_token_mint: Pubkey::new_unique(),
+            swap_fee: SWAP_FEE,
+            bonding_curve_limitation: 85_000_000_000,
+            initial_virtual_base_token: 30_000_000_000,
+            initial_virtual_quote_token: 1_073_000_000_000_000,
+            create_pool_fee_amount: 0,
+        }
+    }
+
+    // Curve after `base_token_reserves` have been bought in
+    fn curve(base_token_reserves: u64) -> BondingCurve {
+        let config = config();
+        let k_value =
+            (config.initial_virtual_base_token as u128) * (config.initial_virtual_quote_token as u128);
+        let virtual_base = (config.initial_virtual_base_token + base_token_reserves) as u128;
+        BondingCurve {
+            init_virtual_base_token: config.initial_virtual_base_token,
+            init_virtual_quote_token: config.initial_virtual_quote_token,
+            quote_token_reserves: (k_value / virtual_base) as u64,
+            base_token_reserves,
+            k_value,
+            is_completed: false,
+            presale_merkle_root: [0; 32],
+            presale_end_time: 0,
+            creator_fee_share: 0,
+        }
+    }
+
+    // 2% capped at 50_000, taking effect at epoch 10
+    fn base_mint_fee(epoch: u64) -> BaseMintFee {
+        let config = TransferFeeConfig {
+            newer_transfer_fee: TransferFee {
+                epoch: 10.into(),
+                maximum_fee: 50_000.into(),
+                transfer_fee_basis_points: 200.into(),
+            },
+            ..Default::default()
+        };
+        BaseMintFee { config, epoch }
+    }
+
+    #[test]
+    fn buy_without_transfer_fee_matches_the_curve() {
+        let curve = curve(0);
+        let amount = 2_000_000_000;
+
+        let quote = quote_buy(&config(), &curve, None, amount).unwrap();
+        let platform_fee = (amount * SWAP_FEE) / 10000;
+        assert_eq!(quote.platform_fee, platform_fee);
+        assert_eq!(quote.amount_out, curve.get_buy_amount_out(amount - platform_fee).unwrap());
+    }
+
+    #[test]
+    fn buy_quotes_on_what_reaches_the_pool() {
+        let curve = curve(0);
+        let amount = 2_000_000_000;
+
+        let quote = quote_buy(&config(), &curve, Some(&base_mint_fee(10)), amount).unwrap();
+        let pool_input = amount - (amount * SWAP_FEE) / 10000;
+        assert_eq!(quote.amount_out, curve.get_buy_amount_out(pool_input - 50_000).unwrap());
+        assert!(quote.amount_out < quote_buy(&config(), &curve, None, amount).unwrap().amount_out);
+    }
+
+    #[test]
+    fn sell_without_transfer_fee_matches_the_curve() {
+        let curve = curve(5_000_000_000);
+        let amount = 10_000_000_000_000;
+
+        let quote = quote_sell(&config(), &curve, None, amount).unwrap();
+        let gross = curve.get_sell_amount_out(amount).unwrap();
+        let platform_fee = (gross * SWAP_FEE) / 10000;
+        assert_eq!(quote.platform_fee, platform_fee);
+        assert_eq!(quote.amount_out, gross - platform_fee);
+    }
+
+    #[test]
+    fn sell_quotes_what_reaches_the_trader() {
+        let curve = curve(5_000_000_000);
+        let amount = 10_000_000_000;
+
+        let quote = quote_sell(&config(), &curve, Some(&base_mint_fee(12)), amount).unwrap();
+        let gross = curve.get_sell_amount_out(amount).unwrap();
+        let pool_output = gross - (gross * SWAP_FEE) / 10000;
+        let transfer_fee = ((pool_output * 200).div_ceil(10000)).min(50_000);
+        assert_eq!(quote.amount_out, pool_output - transfer_fee);
+    }
+
+    #[test]
+    fn transfer_fee_waits_for_its_epoch() {
+        let curve = curve(0);
+        let amount = 2_000_000_000;
+
+        assert_eq!(
+            quote_buy(&config(), &curve, Some(&base_mint_fee(9)), amount).unwrap(),
+            quote_buy(&config(), &curve, None, amount).unwrap()
+        );
+    }
+}
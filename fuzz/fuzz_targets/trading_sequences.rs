@@ -0,0 +1,227 @@
+//! Random launches and trades against the deployed program, checking after every step that:
+//! - the program never panics or aborts (only returns its own errors)
+//! - the virtual reserve product never drops below what it was
+//! - the pool's token accounts always hold at least the recorded reserves
+//! - buying then immediately selling everything never returns more than was spent
+//! - a completed curve rejects every trade until it is migrated
+//!
+//! Run with `cargo fuzz run trading_sequences` after `anchor build` and `tests/fixtures/dump.sh`.
+
+#![no_main]
+
+use std::collections::HashMap;
+
+use anchor_spl::token::ID as TOKEN_PROGRAM_ID;
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use pumpfun_client::{ instructions, pda, BondingCurve };
+use pumpfun_tests::{ SendResult, TestContext };
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{ Keypair, Signer },
+    transaction::TransactionError,
+};
+
+const TRADERS: usize = 3;
+const TRADER_BALANCE: u64 = 1_000_000_000_000_000;
+
+#[derive(Arbitrary, Debug)]
+struct Scenario {
+    swap_fee: u16,
+    creator_fee_share: u16,
+    actions: Vec<Action>,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Action {
+    CreatePool {
+        trader: u8,
+        dev_buy_amount: u64,
+    },
+    Buy {
+        pool: u8,
+        trader: u8,
+        amount: u64,
+    },
+    Sell {
+        pool: u8,
+        trader: u8,
+        /// Share of the trader's balance to sell, in basis points
+        share_bps: u16,
+    },
+    RoundTrip {
+        pool: u8,
+        trader: u8,
+        amount: u64,
+    },
+}
+
+struct Harness {
+    ctx: TestContext,
+    traders: Vec<Keypair>,
+    pools: Vec<Pubkey>,
+    // Last seen (init_virtual_base + base_reserves) * quote_reserves per pool
+    products: HashMap<Pubkey, u128>,
+}
+
+/// Rejections are fine, crashing the program is not
+fn expect_handled(result: SendResult) -> bool {
+    match result {
+        Ok(_) => true,
+        Err(failed) => {
+            if
+                let TransactionError::InstructionError(
+                    _,
+                    InstructionError::ProgramFailedToComplete |
+                    InstructionError::ComputationalBudgetExceeded,
+                ) = failed.err
+            {
+                panic!("program aborted: {:?}\n{:#?}", failed.err, failed.meta.logs);
+            }
+            false
+        }
+    }
+}
+
+impl Harness {
+    fn new(scenario: &Scenario) -> Self {
+        let mut ctx = TestContext::new();
+        let admin = ctx.admin.pubkey();
+        let base_token_mint = ctx.base_token_mint;
+
+        expect_handled(
+            ctx.send_as_admin(
+                instructions::set_swap_fee(&admin, &base_token_mint, (scenario.swap_fee as u64) % 10001)
+            )
+        );
+        expect_handled(
+            ctx.send_as_admin(
+                instructions::set_creator_fee_share(&admin, (scenario.creator_fee_share as u64) % 10001)
+            )
+        );
+
+        let traders = (0..TRADERS).map(|_| ctx.new_user(TRADER_BALANCE)).collect();
+        Self { ctx, traders, pools: Vec::new(), products: HashMap::new() }
+    }
+
+    fn trader(&self, index: u8) -> Keypair {
+        self.traders[(index as usize) % TRADERS].insecure_clone()
+    }
+
+    fn pool(&self, index: u8) -> Option<Pubkey> {
+        if self.pools.is_empty() {
+            return None;
+        }
+        Some(self.pools[(index as usize) % self.pools.len()])
+    }
+
+    fn quote_balance(&self, owner: &Pubkey, mint: &Pubkey) -> u64 {
+        self.ctx.token_balance(&self.ctx.quote_token_ata(owner, mint))
+    }
+
+    fn base_balance(&self, owner: &Pubkey) -> u64 {
+        self.ctx.token_balance(&self.ctx.base_token_ata(owner))
+    }
+
+    fn is_completed(&self, mint: &Pubkey) -> bool {
+        self.ctx.fetch::<BondingCurve>(&pda::bonding_curve(mint)).is_completed
+    }
+
+    /// Trades on a completed curve must fail, whatever they are
+    fn trade(&mut self, mint: &Pubkey, result: impl FnOnce(&mut TestContext) -> SendResult) -> bool {
+        let completed = self.is_completed(mint);
+        let handled = expect_handled(result(&mut self.ctx));
+        assert!(!(completed && handled), "traded on the completed curve of {mint}");
+        handled
+    }
+
+    fn check_invariants(&mut self, mint: &Pubkey) {
+        let curve: BondingCurve = self.ctx.fetch(&pda::bonding_curve(mint));
+        let pool = pda::pool(mint);
+
+        let product =
+            ((curve.init_virtual_base_token as u128) + (curve.base_token_reserves as u128)) *
+            (curve.quote_token_reserves as u128);
+        let previous = self.products.insert(*mint, product).unwrap_or_default();
+        assert!(product >= previous, "reserve product fell from {previous} to {product}");
+
+        let base_vault = self.ctx.token_balance(
+            &pda::associated_token_address(&pool, &self.ctx.base_token_mint, &TOKEN_PROGRAM_ID)
+        );
+        assert!(
+            base_vault >= curve.base_token_reserves,
+            "base vault {base_vault} below reserves {}",
+            curve.base_token_reserves
+        );
+        let quote_vault = self.ctx.token_balance(
+            &pda::associated_token_address(&pool, mint, &TOKEN_PROGRAM_ID)
+        );
+        assert!(
+            quote_vault >= curve.quote_token_reserves,
+            "quote vault {quote_vault} below reserves {}",
+            curve.quote_token_reserves
+        );
+    }
+
+    fn run(&mut self, action: &Action) {
+        match *action {
+            Action::CreatePool { trader, dev_buy_amount } => {
+                let creator = self.trader(trader);
+                let mint = Keypair::new();
+                let amount = dev_buy_amount % (TRADER_BALANCE / 4);
+                if expect_handled(self.ctx.create_pool_with(&creator, &mint, amount)) {
+                    self.pools.push(mint.pubkey());
+                    self.check_invariants(&mint.pubkey());
+                }
+            }
+            Action::Buy { pool, trader, amount } => {
+                let Some(mint) = self.pool(pool) else {
+                    return;
+                };
+                let trader = self.trader(trader);
+                self.trade(&mint, |ctx| ctx.buy(&trader, &mint, amount));
+                self.check_invariants(&mint);
+            }
+            Action::Sell { pool, trader, share_bps } => {
+                let Some(mint) = self.pool(pool) else {
+                    return;
+                };
+                let trader = self.trader(trader);
+                let balance = self.quote_balance(&trader.pubkey(), &mint);
+                let amount = (((balance as u128) * ((share_bps % 10001) as u128)) / 10000) as u64;
+                self.trade(&mint, |ctx| ctx.sell(&trader, &mint, amount));
+                self.check_invariants(&mint);
+            }
+            Action::RoundTrip { pool, trader, amount } => {
+                let Some(mint) = self.pool(pool) else {
+                    return;
+                };
+                let trader = self.trader(trader);
+                let base_before = self.base_balance(&trader.pubkey());
+                let quote_before = self.quote_balance(&trader.pubkey(), &mint);
+
+                if !self.trade(&mint, |ctx| ctx.buy(&trader, &mint, amount)) {
+                    return;
+                }
+                self.check_invariants(&mint);
+                let bought = self.quote_balance(&trader.pubkey(), &mint) - quote_before;
+                self.trade(&mint, |ctx| ctx.sell(&trader, &mint, bought));
+                self.check_invariants(&mint);
+
+                let base_after = self.base_balance(&trader.pubkey());
+                assert!(
+                    base_after <= base_before,
+                    "round trip of {amount} turned {base_before} into {base_after}"
+                );
+            }
+        }
+    }
+}
+
+fuzz_target!(|scenario: Scenario| {
+    let mut harness = Harness::new(&scenario);
+    for action in scenario.actions.iter().take(32) {
+        harness.run(action);
+    }
+});
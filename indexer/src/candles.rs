@@ -0,0 +1,352 @@
+//! OHLCV candles from trade events. Events may arrive out of order, so open and close are
+//! taken from the lowest and highest per-pool trade sequence seen in a bucket rather than
+//! from arrival order.
+
+use std::{ collections::{ BTreeMap, HashSet }, fmt, str::FromStr };
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{ bail, Error };
+use pumpfun_smart_contract::events::{ TradeOperation, TransactionEvent };
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Interval {
+    OneSecond,
+    OneMinute,
+    FiveMinutes,
+    OneHour,
+}
+
+impl Interval {
+    pub const ALL: [Interval; 4] = [
+        Interval::OneSecond,
+        Interval::OneMinute,
+        Interval::FiveMinutes,
+        Interval::OneHour,
+    ];
+
+    pub fn seconds(self) -> i64 {
+        match self {
+            Interval::OneSecond => 1,
+            Interval::OneMinute => 60,
+            Interval::FiveMinutes => 300,
+            Interval::OneHour => 3600,
+        }
+    }
+
+    pub fn bucket_start(self, timestamp: i64) -> i64 {
+        timestamp - timestamp.rem_euclid(self.seconds())
+    }
+}
+
+impl FromStr for Interval {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(match value {
+            "1s" => Interval::OneSecond,
+            "1m" => Interval::OneMinute,
+            "5m" => Interval::FiveMinutes,
+            "1h" => Interval::OneHour,
+            _ => bail!("unknown candle interval {value}, expected 1s, 1m, 5m or 1h"),
+        })
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Interval::OneSecond => "1s",
+            Interval::OneMinute => "1m",
+            Interval::FiveMinutes => "5m",
+            Interval::OneHour => "1h",
+        })
+    }
+}
+
+/// Raw base token atoms per quote token atom
+pub fn price_from_x32(price_x32: u128) -> f64 {
+    (price_x32 as f64) / ((1u64 << 32) as f64)
+}
+
+/// (base, quote) amounts moved by a trade, whatever its direction
+pub fn trade_amounts(event: &TransactionEvent) -> (u64, u64) {
+    match event.operation {
+        TradeOperation::Sell => (event.output_amount, event.input_amount),
+        TradeOperation::Buy | TradeOperation::DevBuy | TradeOperation::PresaleBuy => {
+            (event.input_amount, event.output_amount)
+        }
+    }
+}
+
+/// The parts of a `TransactionEvent` a candle needs
+#[derive(Clone, Copy, Debug)]
+pub struct Trade {
+    pub mint_address: Pubkey,
+    pub sequence: u64,
+    pub timestamp: i64,
+    pub price: f64,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+}
+
+impl From<&TransactionEvent> for Trade {
+    fn from(event: &TransactionEvent) -> Self {
+        let (base_amount, quote_amount) = trade_amounts(event);
+        Self {
+            mint_address: event.mint_address,
+            sequence: event.sequence,
+            timestamp: event.timestamp,
+            price: price_from_x32(event.price_x32),
+            base_amount,
+            quote_amount,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candle {
+    pub mint_address: Pubkey,
+    pub interval: Interval,
+    pub start_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub base_volume: u128,
+    pub quote_volume: u128,
+    pub trades: u64,
+    pub open_sequence: u64,
+    pub close_sequence: u64,
+}
+
+impl Candle {
+    pub fn new(interval: Interval, trade: &Trade) -> Self {
+        Self {
+            mint_address: trade.mint_address,
+            interval,
+            start_time: interval.bucket_start(trade.timestamp),
+            open: trade.price,
+            high: trade.price,
+            low: trade.price,
+            close: trade.price,
+            base_volume: trade.base_amount as u128,
+            quote_volume: trade.quote_amount as u128,
+            trades: 1,
+            open_sequence: trade.sequence,
+            close_sequence: trade.sequence,
+        }
+    }
+
+    /// Folds a trade from this candle's bucket in. Callers are responsible for not applying
+    /// the same trade twice
+    pub fn apply(&mut self, trade: &Trade) {
+        self.high = self.high.max(trade.price);
+        self.low = self.low.min(trade.price);
+        if trade.sequence < self.open_sequence {
+            self.open = trade.price;
+            self.open_sequence = trade.sequence;
+        }
+        if trade.sequence > self.close_sequence {
+            self.close = trade.price;
+            self.close_sequence = trade.sequence;
+        }
+        self.base_volume += trade.base_amount as u128;
+        self.quote_volume += trade.quote_amount as u128;
+        self.trades += 1;
+    }
+}
+
+type CandleKey = (Pubkey, Interval, i64);
+
+/// In-memory aggregation for streaming consumers. Every applied trade returns the candles it
+/// changed, one per configured interval, so they can be pushed out as incremental updates.
+/// Candles and the trades they cover stay in memory until `evict_before` drops them.
+pub struct CandleAggregator {
+    intervals: Vec<Interval>,
+    candles: BTreeMap<CandleKey, Candle>,
+    seen: HashSet<(Pubkey, u64)>,
+}
+
+impl CandleAggregator {
+    pub fn new(intervals: &[Interval]) -> Self {
+        Self {
+            intervals: intervals.to_vec(),
+            candles: BTreeMap::new(),
+            seen: HashSet::new(),
+        }
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn candle(&self, mint_address: Pubkey, interval: Interval, start_time: i64) -> Option<&Candle> {
+        self.candles.get(&(mint_address, interval, start_time))
+    }
+
+    /// Resumes a candle persisted earlier. The trades already in it are not tracked, so the
+    /// caller must keep them from being applied again
+    pub fn restore(&mut self, candle: Candle) {
+        self.candles.entry((candle.mint_address, candle.interval, candle.start_time)).or_insert(candle);
+    }
+
+    /// Duplicate events, e.g. from overlapping RPC pages, return no updates
+    pub fn apply(&mut self, event: &TransactionEvent) -> Vec<Candle> {
+        let trade = Trade::from(event);
+        if !self.seen.insert((trade.mint_address, trade.sequence)) {
+            return Vec::new();
+        }
+
+        self.intervals
+            .iter()
+            .map(|&interval| {
+                let key = (trade.mint_address, interval, interval.bucket_start(trade.timestamp));
+                self.candles
+                    .entry(key)
+                    .and_modify(|candle| candle.apply(&trade))
+                    .or_insert_with(|| Candle::new(interval, &trade))
+                    .clone()
+            })
+            .collect()
+    }
+
+    pub fn candles(&self, mint_address: Pubkey, interval: Interval) -> impl Iterator<Item = &Candle> {
+        self.candles
+            .range((mint_address, interval, i64::MIN)..=(mint_address, interval, i64::MAX))
+            .map(|(_, candle)| candle)
+    }
+
+    /// Drops candles that started before `start_time` and returns them. Trades for those
+    /// buckets arriving later start a fresh candle, so keep a margin for late events.
+    pub fn evict_before(&mut self, start_time: i64) -> Vec<Candle> {
+        let evicted_keys: Vec<CandleKey> = self.candles
+            .keys()
+            .filter(|(_, _, start)| *start < start_time)
+            .copied()
+            .collect();
+        let evicted: Vec<Candle> = evicted_keys
+            .iter()
+            .filter_map(|key| self.candles.remove(key))
+            .collect();
+        // Forget trades no remaining candle covers
+        let candles = &self.candles;
+        self.seen.retain(|(mint_address, sequence)| {
+            candles.values().any(|candle| {
+                candle.mint_address == *mint_address &&
+                    (candle.open_sequence..=candle.close_sequence).contains(sequence)
+            })
+        });
+        evicted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pumpfun_smart_contract::events::TradeOperation;
+
+    fn event(mint_address: Pubkey, sequence: u64, timestamp: i64, price: u128) -> TransactionEvent {
+        TransactionEvent {
+            operation: TradeOperation::Buy,
+            creator: Pubkey::new_unique(),
+            input_amount: 1_000,
+            output_amount: 2_000,
+            platform_fee: 10,
+            base_token_mint: Pubkey::new_unique(),
+            mint_address,
+            fee_wallet: Pubkey::new_unique(),
+            referrer: None,
+            virtual_base_reserves: 0,
+            virtual_quote_reserves: 0,
+            real_base_reserves: 0,
+            real_quote_reserves: 0,
+            price_x32: price << 32,
+            timestamp,
+            slot: 0,
+            sequence,
+        }
+    }
+
+    #[test]
+    fn bucket_start_rounds_down_to_the_interval() {
+        assert_eq!(Interval::OneMinute.bucket_start(59), 0);
+        assert_eq!(Interval::OneMinute.bucket_start(60), 60);
+        assert_eq!(Interval::OneHour.bucket_start(7_199), 3_600);
+        assert_eq!(Interval::OneMinute.bucket_start(-1), -60);
+    }
+
+    #[test]
+    fn out_of_order_trades_open_and_close_by_sequence() {
+        let mint = Pubkey::new_unique();
+        let mut aggregator = CandleAggregator::new(&[Interval::OneMinute]);
+
+        aggregator.apply(&event(mint, 3, 30, 7));
+        aggregator.apply(&event(mint, 1, 10, 5));
+        let updates = aggregator.apply(&event(mint, 2, 20, 9));
+
+        assert_eq!(updates.len(), 1);
+        let candle = &updates[0];
+        assert_eq!((candle.open, candle.close), (5.0, 7.0));
+        assert_eq!((candle.low, candle.high), (5.0, 9.0));
+        assert_eq!((candle.open_sequence, candle.close_sequence), (1, 3));
+        assert_eq!(candle.trades, 3);
+    }
+
+    #[test]
+    fn duplicate_sequences_are_applied_once() {
+        let mint = Pubkey::new_unique();
+        let mut aggregator = CandleAggregator::new(&[Interval::OneSecond, Interval::OneMinute]);
+
+        assert_eq!(aggregator.apply(&event(mint, 1, 10, 5)).len(), 2);
+        assert!(aggregator.apply(&event(mint, 1, 10, 5)).is_empty());
+
+        let candle = aggregator.candle(mint, Interval::OneMinute, 0).unwrap();
+        assert_eq!(candle.trades, 1);
+        assert_eq!((candle.base_volume, candle.quote_volume), (1_000, 2_000));
+
+        // Sequences are per pool
+        let other = Pubkey::new_unique();
+        assert_eq!(aggregator.apply(&event(other, 1, 10, 5)).len(), 2);
+    }
+
+    #[test]
+    fn trades_split_at_bucket_boundaries() {
+        let mint = Pubkey::new_unique();
+        let mut aggregator = CandleAggregator::new(&[Interval::OneMinute, Interval::FiveMinutes]);
+
+        aggregator.apply(&event(mint, 1, 59, 5));
+        aggregator.apply(&event(mint, 2, 60, 6));
+        aggregator.apply(&event(mint, 3, 300, 7));
+
+        let minutes: Vec<_> = aggregator
+            .candles(mint, Interval::OneMinute)
+            .map(|candle| (candle.start_time, candle.trades))
+            .collect();
+        assert_eq!(minutes, [(0, 1), (60, 1), (300, 1)]);
+        let five_minutes: Vec<_> = aggregator
+            .candles(mint, Interval::FiveMinutes)
+            .map(|candle| (candle.start_time, candle.trades))
+            .collect();
+        assert_eq!(five_minutes, [(0, 2), (300, 1)]);
+    }
+
+    #[test]
+    fn evict_before_drops_old_candles_and_their_trades() {
+        let mint = Pubkey::new_unique();
+        let mut aggregator = CandleAggregator::new(&[Interval::OneMinute]);
+        aggregator.apply(&event(mint, 1, 10, 5));
+        aggregator.apply(&event(mint, 2, 70, 6));
+
+        let evicted = aggregator.evict_before(60);
+        assert_eq!(evicted.len(), 1);
+        assert_eq!(evicted[0].start_time, 0);
+        assert_eq!(aggregator.candles(mint, Interval::OneMinute).count(), 1);
+
+        // Trades of remaining candles are still deduplicated
+        assert!(aggregator.apply(&event(mint, 2, 70, 6)).is_empty());
+        // A late trade for an evicted bucket starts a fresh candle
+        let updates = aggregator.apply(&event(mint, 1, 10, 5));
+        assert_eq!(updates[0].start_time, 0);
+        assert_eq!(updates[0].trades, 1);
+    }
+}
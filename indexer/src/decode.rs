@@ -0,0 +1,184 @@
+use anchor_lang::{ event::EVENT_IX_TAG_LE, AnchorDeserialize, Discriminator };
+use anyhow::{ Context, Result };
+use base64::{ engine::general_purpose::STANDARD, Engine };
+use pumpfun_smart_contract::events::{
+    BondingCurveCompleted,
+    CreatorFeesClaimed,
+    MigrationEvent,
+    ProtocolFeesWithdrawn,
+    TokenMetadataUpdated,
+    TransactionEvent,
+};
+
+#[derive(Clone, Debug)]
+pub enum ProgramEvent {
+    Trade(TransactionEvent),
+    BondingCurveCompleted(BondingCurveCompleted),
+    Migration(MigrationEvent),
+    CreatorFeesClaimed(CreatorFeesClaimed),
+    ProtocolFeesWithdrawn(ProtocolFeesWithdrawn),
+    TokenMetadataUpdated(TokenMetadataUpdated),
+}
+
+/// An event together with the transaction it came from
+#[derive(Clone, Debug)]
+pub struct DecodedEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub event: ProgramEvent,
+}
+
+fn deserialize<E: AnchorDeserialize>(payload: &[u8]) -> Result<E> {
+    E::deserialize(&mut &payload[..]).context("malformed event payload")
+}
+
+/// Decodes discriminator + borsh payload. Events this indexer doesn't track return `None`
+pub fn decode_event(data: &[u8]) -> Result<Option<ProgramEvent>> {
+    let Some((discriminator, payload)) = data.split_at_checked(8) else {
+        return Ok(None);
+    };
+
+    let event = if discriminator == TransactionEvent::DISCRIMINATOR {
+        ProgramEvent::Trade(deserialize(payload)?)
+    } else if discriminator == BondingCurveCompleted::DISCRIMINATOR {
+        ProgramEvent::BondingCurveCompleted(deserialize(payload)?)
+    } else if discriminator == MigrationEvent::DISCRIMINATOR {
+        ProgramEvent::Migration(deserialize(payload)?)
+    } else if discriminator == CreatorFeesClaimed::DISCRIMINATOR {
+        ProgramEvent::CreatorFeesClaimed(deserialize(payload)?)
+    } else if discriminator == ProtocolFeesWithdrawn::DISCRIMINATOR {
+        ProgramEvent::ProtocolFeesWithdrawn(deserialize(payload)?)
+    } else if discriminator == TokenMetadataUpdated::DISCRIMINATOR {
+        ProgramEvent::TokenMetadataUpdated(deserialize(payload)?)
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(event))
+}
+
+/// Data of a self-CPI emitted through the event authority
+pub fn decode_cpi_event(ix_data: &[u8]) -> Result<Option<ProgramEvent>> {
+    match ix_data.strip_prefix(EVENT_IX_TAG_LE) {
+        Some(data) => decode_event(data),
+        None => Ok(None),
+    }
+}
+
+/// `Program data:` lines written while the program itself is executing, which is where
+/// builds with the `log-events` feature put their events
+pub fn events_from_logs(logs: &[String], program_id: &str) -> Result<Vec<ProgramEvent>> {
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for log in logs {
+        let Some(rest) = log.strip_prefix("Program ") else {
+            continue;
+        };
+
+        if let Some(data) = rest.strip_prefix("data: ") {
+            if stack.last() != Some(&program_id) {
+                continue;
+            }
+            let bytes = STANDARD.decode(data.trim()).context("invalid base64 in program data")?;
+            if let Some(event) = decode_event(&bytes)? {
+                events.push(event);
+            }
+        } else if let Some((id, status)) = rest.split_once(' ') {
+            if status.starts_with("invoke [") {
+                stack.push(id);
+            } else if status == "success" || status.starts_with("failed") {
+                stack.pop();
+            }
+        }
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{ prelude::Pubkey, Event };
+
+    const PROGRAM_ID: &str = "Prog1111111111111111111111111111111111111111";
+
+    fn claim() -> CreatorFeesClaimed {
+        CreatorFeesClaimed {
+            mint_address: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            amount: 42,
+        }
+    }
+
+    #[test]
+    fn decodes_every_tracked_event() {
+        let claim = claim();
+        let Some(ProgramEvent::CreatorFeesClaimed(decoded)) = decode_event(&claim.data()).unwrap() else {
+            panic!("not decoded as CreatorFeesClaimed");
+        };
+        assert_eq!((decoded.mint_address, decoded.creator, decoded.amount), (claim.mint_address, claim.creator, 42));
+
+        let withdrawn = ProtocolFeesWithdrawn {
+            base_token_mint: Pubkey::new_unique(),
+            amount: u64::MAX,
+            recipients: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+        };
+        let Some(ProgramEvent::ProtocolFeesWithdrawn(decoded)) = decode_event(&withdrawn.data()).unwrap() else {
+            panic!("not decoded as ProtocolFeesWithdrawn");
+        };
+        assert_eq!(decoded.base_token_mint, withdrawn.base_token_mint);
+        assert_eq!(decoded.amount, u64::MAX);
+        assert_eq!(decoded.recipients, withdrawn.recipients);
+
+        let updated = TokenMetadataUpdated {
+            mint_address: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            name: "Renamed".to_string(),
+            symbol: "RNM".to_string(),
+            uri: "https://example.com/renamed.json".to_string(),
+        };
+        let Some(ProgramEvent::TokenMetadataUpdated(decoded)) = decode_event(&updated.data()).unwrap() else {
+            panic!("not decoded as TokenMetadataUpdated");
+        };
+        assert_eq!(decoded.mint_address, updated.mint_address);
+        assert_eq!((decoded.name, decoded.symbol, decoded.uri), (updated.name, updated.symbol, updated.uri));
+    }
+
+    #[test]
+    fn decodes_self_cpi_data() {
+        let claim = claim();
+        let ix_data = [EVENT_IX_TAG_LE, &claim.data()].concat();
+
+        let Some(ProgramEvent::CreatorFeesClaimed(decoded)) = decode_cpi_event(&ix_data).unwrap() else {
+            panic!("not decoded as CreatorFeesClaimed");
+        };
+        assert_eq!(decoded.amount, claim.amount);
+
+        // Instructions other than the event self-CPI are skipped, even if they look like one
+        assert!(decode_cpi_event(&claim.data()).unwrap().is_none());
+    }
+
+    #[test]
+    fn decodes_program_data_logs_of_the_program_only() {
+        let data = format!("Program data: {}", STANDARD.encode(claim().data()));
+        let logs = [
+            format!("Program {PROGRAM_ID} invoke [1]"),
+            data.clone(),
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]".to_string(),
+            data,
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success".to_string(),
+            format!("Program {PROGRAM_ID} success"),
+        ];
+
+        let events = events_from_logs(&logs, PROGRAM_ID).unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], ProgramEvent::CreatorFeesClaimed(_)));
+    }
+
+    #[test]
+    fn rejects_truncated_payloads_and_skips_unknown_events() {
+        let data = claim().data();
+        assert!(decode_event(&data[..data.len() - 1]).is_err());
+        assert!(decode_event(&[0; 16]).unwrap().is_none());
+    }
+}
@@ -0,0 +1,12 @@
+//! Rebuilds trading history from the program's events. `decode` turns transaction logs and
+//! inner instructions into typed events, `candles` aggregates trades into OHLCV candles and
+//! `store` writes all of it into SQLite.
+
+pub mod candles;
+pub mod decode;
+pub mod store;
+pub mod transaction;
+
+pub use candles::{ Candle, CandleAggregator, Interval };
+pub use decode::{ DecodedEvent, ProgramEvent };
+pub use transaction::RawTransaction;
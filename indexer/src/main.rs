@@ -0,0 +1,71 @@
+use std::{ fs::File, io::{ BufRead, BufReader } };
+
+use anyhow::{ Context, Result };
+use clap::Parser;
+use pumpfun_indexer::{ store, CandleAggregator, Interval, ProgramEvent, RawTransaction };
+use rusqlite::Connection;
+
+// Candles older than this, relative to the newest trade, are dropped from memory. `store`
+// reloads them from the database if a late trade still lands in one
+const CANDLE_RETENTION_SECONDS: i64 = 2 * 3600;
+const EVICT_EVERY_TRANSACTIONS: usize = 1_000;
+
+#[derive(Parser)]
+#[command(name = "pumpfun-indexer", about = "Index a JSONL dump of transactions into SQLite")]
+struct Args {
+    /// One `getTransaction` json response per line
+    #[arg(long)]
+    input: String,
+
+    #[arg(long, default_value = "pumpfun.sqlite")]
+    db: String,
+
+    #[arg(long, default_value_t = pumpfun_smart_contract::ID.to_string())]
+    program_id: String,
+
+    /// Candle intervals to maintain: 1s, 1m, 5m or 1h. Defaults to all of them
+    #[arg(long = "interval")]
+    intervals: Vec<Interval>,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let intervals = if args.intervals.is_empty() {
+        Interval::ALL.to_vec()
+    } else {
+        args.intervals.clone()
+    };
+    let input = File::open(&args.input).with_context(|| format!("failed to open {}", args.input))?;
+
+    let mut conn = Connection::open(&args.db)?;
+    store::init(&conn)?;
+    let db = conn.transaction()?;
+    let mut aggregator = CandleAggregator::new(&intervals);
+
+    let (mut transactions, mut events) = (0usize, 0usize);
+    let mut latest_timestamp = i64::MIN;
+    for (line_number, line) in BufReader::new(input).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let tx = RawTransaction::from_json(&line).with_context(||
+            format!("line {}", line_number + 1)
+        )?;
+        for decoded in tx.events(&args.program_id)? {
+            if let ProgramEvent::Trade(event) = &decoded.event {
+                latest_timestamp = latest_timestamp.max(event.timestamp);
+            }
+            store::apply(&db, &decoded, &mut aggregator)?;
+            events += 1;
+        }
+        transactions += 1;
+        if transactions % EVICT_EVERY_TRANSACTIONS == 0 {
+            aggregator.evict_before(latest_timestamp.saturating_sub(CANDLE_RETENTION_SECONDS));
+        }
+    }
+    db.commit()?;
+
+    println!("indexed {events} events from {transactions} transactions into {}", args.db);
+    Ok(())
+}
@@ -0,0 +1,392 @@
+use anyhow::Result;
+use pumpfun_smart_contract::events::{ TradeOperation, TransactionEvent };
+use rusqlite::{ params, types::Type, Connection, OptionalExtension, Row };
+
+use crate::candles::{ price_from_x32, trade_amounts, Candle, CandleAggregator, Interval, Trade };
+use crate::decode::{ DecodedEvent, ProgramEvent };
+
+/// Token amounts and volumes are decimal TEXT, SQLite integers are signed 64 bit and can't
+/// hold every u64. Slots, timestamps and sequences are far from that limit and stay INTEGER.
+pub fn init(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS pools (
+            mint_address TEXT PRIMARY KEY,
+            base_token_mint TEXT NOT NULL,
+            creator TEXT NOT NULL,
+            created_slot INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            completed INTEGER NOT NULL DEFAULT 0,
+            raydium_pool TEXT
+        );
+        CREATE TABLE IF NOT EXISTS trades (
+            mint_address TEXT NOT NULL,
+            sequence INTEGER NOT NULL,
+            signature TEXT NOT NULL,
+            operation TEXT NOT NULL,
+            trader TEXT NOT NULL,
+            base_amount TEXT NOT NULL,
+            quote_amount TEXT NOT NULL,
+            platform_fee TEXT NOT NULL,
+            referrer TEXT,
+            virtual_base_reserves TEXT NOT NULL,
+            virtual_quote_reserves TEXT NOT NULL,
+            price_x32 TEXT NOT NULL,
+            price REAL NOT NULL,
+            timestamp INTEGER NOT NULL,
+            slot INTEGER NOT NULL,
+            PRIMARY KEY (mint_address, sequence)
+        );
+        CREATE TABLE IF NOT EXISTS candles (
+            mint_address TEXT NOT NULL,
+            interval INTEGER NOT NULL,
+            start_time INTEGER NOT NULL,
+            open REAL NOT NULL,
+            high REAL NOT NULL,
+            low REAL NOT NULL,
+            close REAL NOT NULL,
+            base_volume TEXT NOT NULL,
+            quote_volume TEXT NOT NULL,
+            trades INTEGER NOT NULL,
+            open_sequence INTEGER NOT NULL,
+            close_sequence INTEGER NOT NULL,
+            PRIMARY KEY (mint_address, interval, start_time)
+        );
+        CREATE TABLE IF NOT EXISTS creator_fee_claims (
+            signature TEXT NOT NULL,
+            mint_address TEXT NOT NULL,
+            creator TEXT NOT NULL,
+            amount TEXT NOT NULL,
+            slot INTEGER NOT NULL,
+            PRIMARY KEY (signature, mint_address)
+        );
+        CREATE TABLE IF NOT EXISTS protocol_fee_withdrawals (
+            signature TEXT NOT NULL,
+            base_token_mint TEXT NOT NULL,
+            amount TEXT NOT NULL,
+            recipients TEXT NOT NULL,
+            slot INTEGER NOT NULL,
+            PRIMARY KEY (signature, base_token_mint)
+        );
+        CREATE TABLE IF NOT EXISTS token_metadata (
+            mint_address TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            symbol TEXT NOT NULL,
+            uri TEXT NOT NULL,
+            updated_slot INTEGER NOT NULL
+        );"
+    )?;
+    Ok(())
+}
+
+pub fn operation_name(operation: TradeOperation) -> &'static str {
+    match operation {
+        TradeOperation::Buy => "buy",
+        TradeOperation::Sell => "sell",
+        TradeOperation::DevBuy => "dev_buy",
+        TradeOperation::PresaleBuy => "presale_buy",
+    }
+}
+
+/// Re-applying an event already stored is a no-op, so dumps can be replayed. Candles are
+/// built by `aggregator`, resuming the stored ones it doesn't hold yet
+pub fn apply(conn: &Connection, decoded: &DecodedEvent, aggregator: &mut CandleAggregator) -> Result<()> {
+    match &decoded.event {
+        ProgramEvent::Trade(event) => apply_trade(conn, &decoded.signature, event, aggregator),
+        ProgramEvent::BondingCurveCompleted(event) => {
+            conn.execute(
+                "UPDATE pools SET completed = 1 WHERE mint_address = ?1",
+                params![event.mint_address.to_string()]
+            )?;
+            Ok(())
+        }
+        ProgramEvent::Migration(event) => {
+            conn.execute(
+                "UPDATE pools SET raydium_pool = ?2 WHERE mint_address = ?1",
+                params![event.mint_address.to_string(), event.raydium_pool.to_string()]
+            )?;
+            Ok(())
+        }
+        ProgramEvent::CreatorFeesClaimed(event) => {
+            conn.execute(
+                "INSERT OR IGNORE INTO creator_fee_claims (signature, mint_address, creator, amount, slot)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    decoded.signature,
+                    event.mint_address.to_string(),
+                    event.creator.to_string(),
+                    event.amount.to_string(),
+                    decoded.slot
+                ]
+            )?;
+            Ok(())
+        }
+        ProgramEvent::ProtocolFeesWithdrawn(event) => {
+            let recipients: Vec<String> = event.recipients
+                .iter()
+                .map(|recipient| recipient.to_string())
+                .collect();
+            conn.execute(
+                "INSERT OR IGNORE INTO protocol_fee_withdrawals (signature, base_token_mint, amount, recipients, slot)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    decoded.signature,
+                    event.base_token_mint.to_string(),
+                    event.amount.to_string(),
+                    recipients.join(","),
+                    decoded.slot
+                ]
+            )?;
+            Ok(())
+        }
+        // Keeps the latest update whatever order the events are indexed in
+        ProgramEvent::TokenMetadataUpdated(event) => {
+            conn.execute(
+                "INSERT INTO token_metadata (mint_address, name, symbol, uri, updated_slot)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT (mint_address) DO UPDATE SET
+                    name = excluded.name, symbol = excluded.symbol, uri = excluded.uri,
+                    updated_slot = excluded.updated_slot
+                 WHERE excluded.updated_slot >= token_metadata.updated_slot",
+                params![
+                    event.mint_address.to_string(),
+                    event.name,
+                    event.symbol,
+                    event.uri,
+                    decoded.slot
+                ]
+            )?;
+            Ok(())
+        }
+    }
+}
+
+fn apply_trade(
+    conn: &Connection,
+    signature: &str,
+    event: &TransactionEvent,
+    aggregator: &mut CandleAggregator
+) -> Result<()> {
+    let mint_address = event.mint_address.to_string();
+
+    if event.operation == TradeOperation::DevBuy {
+        conn.execute(
+            "INSERT OR IGNORE INTO pools (mint_address, base_token_mint, creator, created_slot, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                mint_address,
+                event.base_token_mint.to_string(),
+                event.creator.to_string(),
+                event.slot,
+                event.timestamp
+            ]
+        )?;
+    }
+
+    let (base_amount, quote_amount) = trade_amounts(event);
+    let price = price_from_x32(event.price_x32);
+    let inserted = conn.execute(
+        "INSERT OR IGNORE INTO trades (
+            mint_address, sequence, signature, operation, trader, base_amount, quote_amount,
+            platform_fee, referrer, virtual_base_reserves, virtual_quote_reserves, price_x32,
+            price, timestamp, slot
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        params![
+            mint_address,
+            event.sequence,
+            signature,
+            operation_name(event.operation),
+            event.creator.to_string(),
+            base_amount.to_string(),
+            quote_amount.to_string(),
+            event.platform_fee.to_string(),
+            event.referrer.map(|referrer| referrer.to_string()),
+            event.virtual_base_reserves.to_string(),
+            event.virtual_quote_reserves.to_string(),
+            event.price_x32.to_string(),
+            price,
+            event.timestamp,
+            event.slot
+        ]
+    )?;
+    if inserted == 0 {
+        return Ok(());
+    }
+
+    let trade = Trade::from(event);
+    for interval in aggregator.intervals().to_vec() {
+        let start_time = interval.bucket_start(trade.timestamp);
+        if aggregator.candle(trade.mint_address, interval, start_time).is_none() {
+            if let Some(candle) = load_candle(conn, &trade, interval)? {
+                aggregator.restore(candle);
+            }
+        }
+    }
+    for candle in aggregator.apply(event) {
+        save_candle(conn, &candle)?;
+    }
+    Ok(())
+}
+
+fn get_amount(row: &Row, index: usize) -> rusqlite::Result<u128> {
+    row.get::<_, String>(index)?
+        .parse()
+        .map_err(|err| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(err)))
+}
+
+fn load_candle(conn: &Connection, trade: &Trade, interval: Interval) -> Result<Option<Candle>> {
+    let start_time = interval.bucket_start(trade.timestamp);
+    let candle = conn
+        .query_row(
+            "SELECT open, high, low, close, base_volume, quote_volume, trades, open_sequence, close_sequence
+             FROM candles WHERE mint_address = ?1 AND interval = ?2 AND start_time = ?3",
+            params![trade.mint_address.to_string(), interval.seconds(), start_time],
+            |row| {
+                Ok(Candle {
+                    mint_address: trade.mint_address,
+                    interval,
+                    start_time,
+                    open: row.get(0)?,
+                    high: row.get(1)?,
+                    low: row.get(2)?,
+                    close: row.get(3)?,
+                    base_volume: get_amount(row, 4)?,
+                    quote_volume: get_amount(row, 5)?,
+                    trades: row.get(6)?,
+                    open_sequence: row.get(7)?,
+                    close_sequence: row.get(8)?,
+                })
+            }
+        )
+        .optional()?;
+    Ok(candle)
+}
+
+fn save_candle(conn: &Connection, candle: &Candle) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO candles (
+            mint_address, interval, start_time, open, high, low, close, base_volume, quote_volume,
+            trades, open_sequence, close_sequence
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            candle.mint_address.to_string(),
+            candle.interval.seconds(),
+            candle.start_time,
+            candle.open,
+            candle.high,
+            candle.low,
+            candle.close,
+            candle.base_volume.to_string(),
+            candle.quote_volume.to_string(),
+            candle.trades,
+            candle.open_sequence,
+            candle.close_sequence
+        ]
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use pumpfun_smart_contract::events::TokenMetadataUpdated;
+
+    fn trade(mint_address: Pubkey, sequence: u64, input_amount: u64) -> DecodedEvent {
+        DecodedEvent {
+            signature: format!("signature-{sequence}"),
+            slot: sequence,
+            block_time: None,
+            event: ProgramEvent::Trade(TransactionEvent {
+                operation: TradeOperation::Buy,
+                creator: Pubkey::new_unique(),
+                input_amount,
+                output_amount: 1,
+                platform_fee: u64::MAX,
+                base_token_mint: Pubkey::new_unique(),
+                mint_address,
+                fee_wallet: Pubkey::new_unique(),
+                referrer: None,
+                virtual_base_reserves: u64::MAX,
+                virtual_quote_reserves: 1,
+                real_base_reserves: 0,
+                real_quote_reserves: 0,
+                price_x32: 1 << 32,
+                timestamp: 10,
+                slot: sequence,
+                sequence,
+            }),
+        }
+    }
+
+    #[test]
+    fn amounts_above_i64_round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        init(&conn).unwrap();
+        let mint = Pubkey::new_unique();
+        let mut aggregator = CandleAggregator::new(&[Interval::OneMinute]);
+
+        apply(&conn, &trade(mint, 1, u64::MAX), &mut aggregator).unwrap();
+        apply(&conn, &trade(mint, 2, u64::MAX), &mut aggregator).unwrap();
+
+        let (base_amount, platform_fee): (String, String) = conn
+            .query_row("SELECT base_amount, platform_fee FROM trades WHERE sequence = 1", [], |row|
+                Ok((row.get(0)?, row.get(1)?))
+            )
+            .unwrap();
+        assert_eq!(base_amount, u64::MAX.to_string());
+        assert_eq!(platform_fee, u64::MAX.to_string());
+        let base_volume: String = conn
+            .query_row("SELECT base_volume FROM candles", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(base_volume, (2 * (u64::MAX as u128)).to_string());
+    }
+
+    #[test]
+    fn replays_resume_stored_candles() {
+        let conn = Connection::open_in_memory().unwrap();
+        init(&conn).unwrap();
+        let mint = Pubkey::new_unique();
+
+        let mut first_run = CandleAggregator::new(&[Interval::OneMinute]);
+        apply(&conn, &trade(mint, 1, 100), &mut first_run).unwrap();
+
+        // A later run starts empty, sees the stored trade again and one new trade
+        let mut second_run = CandleAggregator::new(&[Interval::OneMinute]);
+        apply(&conn, &trade(mint, 1, 100), &mut second_run).unwrap();
+        apply(&conn, &trade(mint, 2, 50), &mut second_run).unwrap();
+
+        let (trades, base_volume): (u64, String) = conn
+            .query_row("SELECT trades, base_volume FROM candles", [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+        assert_eq!(trades, 2);
+        assert_eq!(base_volume, "150");
+    }
+
+    #[test]
+    fn metadata_keeps_the_latest_update() {
+        let conn = Connection::open_in_memory().unwrap();
+        init(&conn).unwrap();
+        let mut aggregator = CandleAggregator::new(&[]);
+        let mint_address = Pubkey::new_unique();
+        let update = |slot: u64, symbol: &str| DecodedEvent {
+            signature: format!("signature-{slot}"),
+            slot,
+            block_time: None,
+            event: ProgramEvent::TokenMetadataUpdated(TokenMetadataUpdated {
+                mint_address,
+                creator: Pubkey::new_unique(),
+                name: "Renamed".to_string(),
+                symbol: symbol.to_string(),
+                uri: "https://example.com/renamed.json".to_string(),
+            }),
+        };
+
+        apply(&conn, &update(20, "NEW"), &mut aggregator).unwrap();
+        apply(&conn, &update(10, "OLD"), &mut aggregator).unwrap();
+
+        let symbol: String = conn
+            .query_row("SELECT symbol FROM token_metadata", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(symbol, "NEW");
+    }
+}
@@ -0,0 +1,131 @@
+//! The subset of a `getTransaction` response (with `"encoding": "json"`) the indexer reads.
+//! Dumps are one such response per line.
+
+use anyhow::{ Context, Result };
+use serde::Deserialize;
+
+use crate::decode::{ decode_cpi_event, events_from_logs, DecodedEvent };
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawTransaction {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub transaction: RawTransactionBody,
+    pub meta: Option<RawMeta>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RawTransactionBody {
+    pub signatures: Vec<String>,
+    pub message: RawMessage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawMessage {
+    pub account_keys: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawMeta {
+    pub err: Option<serde_json::Value>,
+    #[serde(default)]
+    pub log_messages: Option<Vec<String>>,
+    #[serde(default)]
+    pub inner_instructions: Option<Vec<RawInnerInstructions>>,
+    #[serde(default)]
+    pub loaded_addresses: Option<RawLoadedAddresses>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RawInnerInstructions {
+    pub index: u8,
+    pub instructions: Vec<RawInstruction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawInstruction {
+    pub program_id_index: u8,
+    /// base58
+    pub data: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RawLoadedAddresses {
+    #[serde(default)]
+    pub writable: Vec<String>,
+    #[serde(default)]
+    pub readonly: Vec<String>,
+}
+
+impl RawTransaction {
+    pub fn from_json(line: &str) -> Result<Self> {
+        serde_json::from_str(line).context("invalid transaction json")
+    }
+
+    pub fn signature(&self) -> &str {
+        self.transaction.signatures.first().map(String::as_str).unwrap_or_default()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.meta.as_ref().is_some_and(|meta| meta.err.is_none())
+    }
+
+    // Static keys followed by lookup table addresses, which is how instructions index them
+    fn account_keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.transaction.message.account_keys
+            .iter()
+            .map(String::as_str)
+            .collect();
+        if let Some(loaded) = self.meta.as_ref().and_then(|meta| meta.loaded_addresses.as_ref()) {
+            keys.extend(loaded.writable.iter().map(String::as_str));
+            keys.extend(loaded.readonly.iter().map(String::as_str));
+        }
+        keys
+    }
+
+    /// Events emitted by `program_id`, in execution order. Failed transactions have none.
+    /// Self-CPI events come from inner instructions, `log-events` builds from the logs.
+    pub fn events(&self, program_id: &str) -> Result<Vec<DecodedEvent>> {
+        let Some(meta) = self.meta.as_ref().filter(|_| self.is_success()) else {
+            return Ok(Vec::new());
+        };
+
+        let keys = self.account_keys();
+        let mut events = Vec::new();
+
+        let mut inner_instructions: Vec<&RawInnerInstructions> = meta.inner_instructions
+            .iter()
+            .flatten()
+            .collect();
+        inner_instructions.sort_by_key(|inner| inner.index);
+        for ix in inner_instructions.iter().flat_map(|inner| &inner.instructions) {
+            if keys.get(ix.program_id_index as usize) != Some(&program_id) {
+                continue;
+            }
+            let data = bs58::decode(&ix.data).into_vec().context("invalid base58 instruction data")?;
+            if let Some(event) = decode_cpi_event(&data)? {
+                events.push(event);
+            }
+        }
+
+        if let Some(logs) = &meta.log_messages {
+            events.extend(events_from_logs(logs, program_id)?);
+        }
+
+        Ok(
+            events
+                .into_iter()
+                .map(|event| DecodedEvent {
+                    signature: self.signature().to_string(),
+                    slot: self.slot,
+                    block_time: self.block_time,
+                    event,
+                })
+                .collect()
+        )
+    }
+}
@@ -1,5 +1,34 @@
-use anchor_lang::prelude::*;
+use anchor_lang::prelude::Pubkey;
 
 pub const CONFIG_SEED: &'static str = "initial_config";
+pub const BASE_TOKEN_CONFIG_SEED: &'static str = "base_token_config";
 pub const CURVE_SEED: &'static str = "bonding_curve";
 pub const POOL_SEED: &'static str = "platform_vault";
+pub const MINT_SEED: &'static str = "meme_mint";
+pub const OBSERVATION_SEED: &'static str = "observation";
+pub const EVENT_AUTHORITY_SEED: &'static str = "__event_authority";
+pub const PRESALE_SEED: &'static str = "presale_receipt";
+pub const CREATOR_VAULT_SEED: &'static str = "creator_vault";
+pub const REFERRER_SEED: &'static str = "referrer";
+pub const PROTOCOL_FEE_VAULT_SEED: &'static str = "protocol_fee_vault";
+pub const LIQUIDITY_OWNER_SEED: &'static str = "liquidity_owner";
+
+// Lamports sent along with the migration fee to pay rent for the Raydium pool accounts
+pub const TX_CONFIRM_FEE: u64 = 10_000_000;
+
+// The Raydium CP-Swap deployment `proxy_initialize` migrates to
+pub const CP_SWAP_PROGRAM_ID: Pubkey = raydium_cp_swap::ID;
+
+pub const MAX_FEE_RECIPIENTS: usize = 4;
+
+pub const QUOTE_TOKEN_DECIMALS: u8 = 6;
+
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_SYMBOL_LENGTH: usize = 10;
+pub const MAX_URI_LENGTH: usize = 200;
+pub const ALLOWED_URI_SCHEMES: [&'static str; 3] = ["https://", "ipfs://", "ar://"];
+
+// Fixed size Metaplex allocates for every metadata account
+pub const METADATA_ACCOUNT_SIZE: usize = 679;
+
+pub const OBSERVATION_NUM: usize = 100;
@@ -58,4 +58,76 @@ pub enum CustomError {
 
     #[msg("Invalid Open Time!")]
     InvalidOpenTime,
+
+    #[msg("Invalid Presale End Time!")]
+    InvalidPresaleEndTime,
+
+    #[msg("Presale Is Active!")]
+    PresaleIsActive,
+
+    #[msg("Presale Is Not Active!")]
+    PresaleIsNotActive,
+
+    #[msg("Invalid Presale Proof!")]
+    InvalidPresaleProof,
+
+    #[msg("Presale Allocation Exceeded!")]
+    PresaleAllocationExceeded,
+
+    #[msg("Invalid Fee Share!")]
+    InvalidFeeShare,
+
+    #[msg("Invalid Creator!")]
+    InvalidCreator,
+
+    #[msg("Nothing To Claim!")]
+    NothingToClaim,
+
+    #[msg("Invalid Referrer!")]
+    InvalidReferrer,
+
+    #[msg("Invalid Fee Recipients!")]
+    InvalidFeeRecipients,
+
+    #[msg("Invalid Token Program!")]
+    InvalidTokenProgram,
+
+    #[msg("Invalid Mint Address!")]
+    InvalidMintAddress,
+
+    #[msg("Token Name Is Empty!")]
+    EmptyTokenName,
+
+    #[msg("Token Name Is Too Long!")]
+    TokenNameTooLong,
+
+    #[msg("Token Symbol Is Empty!")]
+    EmptyTokenSymbol,
+
+    #[msg("Token Symbol Is Too Long!")]
+    TokenSymbolTooLong,
+
+    #[msg("Token Symbol Must Be Alphanumeric!")]
+    InvalidTokenSymbol,
+
+    #[msg("Token Uri Is Too Long!")]
+    TokenUriTooLong,
+
+    #[msg("Token Uri Scheme Is Not Allowed!")]
+    InvalidTokenUriScheme,
+
+    #[msg("Metadata Accounts Are Missing!")]
+    MissingMetadataAccounts,
+
+    #[msg("Not Enough Price Observations For The Window!")]
+    InsufficientObservations,
+
+    #[msg("Swap Fee Is Above 100%!")]
+    InvalidSwapFee,
+
+    #[msg("Traders Cannot Refer Themselves!")]
+    SelfReferral,
+
+    #[msg("Pool Is Already Migrated!")]
+    AlreadyMigrated,
 }
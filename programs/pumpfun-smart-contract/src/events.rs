@@ -1,6 +1,12 @@
 use anchor_lang::prelude::*;
 
+// Events are emitted with `emit_cpi!` by default so they land in inner instruction data, which
+// RPC providers don't truncate like program logs. Building with the `log-events` feature
+// switches to `emit!`; the event authority accounts stay in every instruction either way, so
+// clients don't depend on how the program was built.
+
 #[event]
+#[derive(Clone, Debug)]
 pub struct BondingCurveCompleted {
     pub mint_address: Pubkey,
     pub user_quote_token_ata: Pubkey,
@@ -8,21 +14,69 @@ pub struct BondingCurveCompleted {
     pub quote_token_pool: Pubkey,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TradeOperation {
+    Buy,
+    Sell,
+    DevBuy,
+    PresaleBuy,
+}
+
 #[event]
+#[derive(Clone, Debug)]
 pub struct TransactionEvent {
-    pub operation: String,
+    pub operation: TradeOperation,
     pub creator: Pubkey,
     pub input_amount: u64,
+    // What the trader received, after fees
     pub output_amount: u64,
     pub platform_fee: u64,
     pub base_token_mint: Pubkey,
     pub mint_address: Pubkey,
     pub fee_wallet: Pubkey,
+    pub referrer: Option<Pubkey>,
+    // Curve state after the trade
+    pub virtual_base_reserves: u64,
+    pub virtual_quote_reserves: u64,
+    pub real_base_reserves: u64,
+    pub real_quote_reserves: u64,
+    pub price_x32: u128,
+    pub timestamp: i64,
+    pub slot: u64,
+    // Per-pool trade counter starting at 1 with the dev buy, a skipped value means a missed event
+    pub sequence: u64,
 }
 
 #[event]
+#[derive(Clone, Debug)]
 pub struct MigrationEvent {
     pub mint_address: Pubkey,
     pub raydium_pool: Pubkey,
     pub contract: Pubkey,
 }
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct CreatorFeesClaimed {
+    pub mint_address: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProtocolFeesWithdrawn {
+    pub base_token_mint: Pubkey,
+    pub amount: u64,
+    pub recipients: Vec<Pubkey>,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct TokenMetadataUpdated {
+    pub mint_address: Pubkey,
+    pub creator: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
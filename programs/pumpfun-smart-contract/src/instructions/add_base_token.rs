@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint as InterfaceMint;
+
+use crate::state::{ InitializeConfiguration, BaseTokenConfiguration };
+use crate::consts::*;
+use crate::errors::CustomError;
+
+#[derive(Accounts)]
+pub struct AddBaseToken<'info> {
+    #[account(seeds = [CONFIG_SEED.as_bytes()], bump)]
+    pub global_configuration: Account<'info, InitializeConfiguration>,
+
+    #[account(
+        init,
+        seeds = [BASE_TOKEN_CONFIG_SEED.as_bytes(), &base_token_mint.key().to_bytes()],
+        payer = admin,
+        space = 8 + BaseTokenConfiguration::SIZE,
+        bump
+    )]
+    pub base_token_configuration: Account<'info, BaseTokenConfiguration>,
+
+    pub base_token_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        constraint = admin.key() == global_configuration.admin.key() @ CustomError::InvalidAdminAccount
+    )]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<AddBaseToken>,
+    swap_fee: u64,
+    bonding_curve_limitation: u64,
+    initial_virtual_base_token: u64,
+    initial_virtual_quote_token: u64,
+    create_pool_fee_amount: u64
+) -> Result<()> {
+    require!(swap_fee <= 10000, CustomError::InvalidSwapFee);
+    let config = &mut ctx.accounts.base_token_configuration;
+
+    config.base_token_mint = ctx.accounts.base_token_mint.key();
+    config.swap_fee = swap_fee;
+    config.bonding_curve_limitation = bonding_curve_limitation;
+    config.initial_virtual_base_token = initial_virtual_base_token;
+    config.initial_virtual_quote_token = initial_virtual_quote_token;
+    config.create_pool_fee_amount = create_pool_fee_amount;
+
+    Ok(())
+}
@@ -1,51 +1,82 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{ self, Token, Mint, TokenAccount, TransferChecked, transfer_checked },
+    token_interface::{
+        transfer_checked,
+        Mint as InterfaceMint,
+        TokenAccount as TokenInterAccount,
+        TokenInterface,
+        TransferChecked,
+    },
+};
+use crate::{ consts::*, events::{ BondingCurveCompleted, TradeOperation, TransactionEvent } };
+use crate::state::{
+    BondingCurve,
+    BaseTokenConfiguration,
+    InitializeConfiguration,
+    ObservationState,
+    Pool,
+    Referrer,
 };
-use crate::{ consts::*, events::{ BondingCurveCompleted, TransactionEvent } };
-use crate::state::{ BondingCurve, InitializeConfiguration };
 use crate::errors::CustomError;
+use crate::utils::{ get_transfer_fee, is_native_mint, unwrap_sol, wrap_sol };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Buy<'info> {
     #[account(mut, seeds = [CONFIG_SEED.as_bytes()], bump)]
     pub global_configuration: Account<'info, InitializeConfiguration>,
 
+    #[account(
+        seeds = [BASE_TOKEN_CONFIG_SEED.as_bytes(), &base_token_mint.key().to_bytes()],
+        bump
+    )]
+    pub base_token_configuration: Account<'info, BaseTokenConfiguration>,
+
     #[account(
       mut,
       seeds = [ &mint_address.key().to_bytes(), CURVE_SEED.as_bytes()],
-      bump
+      bump,
+      constraint = !bonding_curve.is_completed @ CustomError::BondingCurveIsCompleted
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
 
-    pub mint_address: Account<'info, Mint>,
+    #[account(
+      mut,
+      seeds = [&mint_address.key().to_bytes(), OBSERVATION_SEED.as_bytes()],
+      bump
+    )]
+    pub price_observation: AccountLoader<'info, ObservationState>,
+
+    pub mint_address: InterfaceAccount<'info, InterfaceMint>,
 
-    pub base_token_mint: Account<'info, Mint>,
+    pub base_token_mint: InterfaceAccount<'info, InterfaceMint>,
 
     #[account(
-        mut,
+        init_if_needed,
+        payer = payer,
         associated_token::mint = base_token_mint,
         associated_token::authority = payer,
         associated_token::token_program = token_program,
     )]
-    pub user_base_token_ata: Account<'info, TokenAccount>,
+    pub user_base_token_ata: InterfaceAccount<'info, TokenInterAccount>,
 
     #[account(
-        mut,
+        init_if_needed,
+        payer = payer,
         associated_token::mint = mint_address,
         associated_token::authority = payer,
-        associated_token::token_program = token_program
+        associated_token::token_program = quote_token_program
     )]
-    pub user_quote_token_ata: Account<'info, TokenAccount>,
+    pub user_quote_token_ata: InterfaceAccount<'info, TokenInterAccount>,
 
-    ///CHECK:
     #[account(
       mut,
       seeds = [&mint_address.key().to_bytes(), POOL_SEED.as_bytes()],
-      bump
+      bump,
+      constraint = pool.base_token_mint == base_token_mint.key() @ CustomError::InvalidBaseToken
     )]
-    pub pool: AccountInfo<'info>,
+    pub pool: Account<'info, Pool>,
 
     ///CHECK:
     #[account(
@@ -54,82 +85,220 @@ pub struct Buy<'info> {
         associated_token::authority = pool,
         associated_token::token_program = token_program
     )]
-    pub base_token_pool: Account<'info, TokenAccount>,
+    pub base_token_pool: InterfaceAccount<'info, TokenInterAccount>,
 
     ///CHECK:
     #[account(
       mut, 
       associated_token::mint = mint_address,
       associated_token::authority = pool,
-      associated_token::token_program = token_program
+      associated_token::token_program = quote_token_program
     )]
-    pub quote_token_pool: Account<'info, TokenAccount>,
+    pub quote_token_pool: InterfaceAccount<'info, TokenInterAccount>,
 
     ///CHECK:
-    #[account(mut)]
-    pub fee_account: AccountInfo<'info>,
+    #[account(
+      seeds = [PROTOCOL_FEE_VAULT_SEED.as_bytes(), &base_token_mint.key().to_bytes()],
+      bump
+    )]
+    pub protocol_fee_vault: AccountInfo<'info>,
 
     #[account(
         mut,
         associated_token::mint = base_token_mint,
-        associated_token::authority = fee_account,
+        associated_token::authority = protocol_fee_vault,
         associated_token::token_program = token_program
     )]
-    pub fee_base_token_ata: Account<'info, TokenAccount>,
+    pub protocol_fee_vault_ata: InterfaceAccount<'info, TokenInterAccount>,
+
+    ///CHECK:
+    #[account(
+      seeds = [&mint_address.key().to_bytes(), CREATOR_VAULT_SEED.as_bytes()],
+      bump
+    )]
+    pub creator_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = creator_vault,
+        associated_token::token_program = token_program
+    )]
+    pub creator_vault_base_token_ata: InterfaceAccount<'info, TokenInterAccount>,
+
+    #[account(mut, seeds = [REFERRER_SEED.as_bytes(), &referrer.wallet.to_bytes()], bump)]
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    #[account(mut)]
+    pub referrer_base_token_ata: Option<InterfaceAccount<'info, TokenInterAccount>>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<Buy>, base_input_amount: u64, expected_amount: u64) -> Result<()> {
-    let bonding_curve = &mut ctx.accounts.bonding_curve;
-    let k_value = bonding_curve.k_value;
-    let platform_fee =
-        (base_input_amount * ctx.accounts.global_configuration.swap_fee.clone()) / 10000;
-    let denominator = (bonding_curve.init_virtual_base_token +
-        bonding_curve.base_token_reserves +
-        (base_input_amount - platform_fee)) as u128;
-    let maybe_quote = (bonding_curve.quote_token_reserves as u128).checked_sub(
-        k_value / denominator
+    require!(
+        !ctx.accounts.bonding_curve.is_presale_active(Clock::get()?.unix_timestamp),
+        CustomError::PresaleIsActive
     );
-    let estimated_out_quote = maybe_quote.ok_or_else(||
-        error!(CustomError::OverflowEstimateOutQuote)
-    )? as u64;
+
+    process(ctx, TradeOperation::Buy, base_input_amount, expected_amount)
+}
+
+// Shared with presale_buy, which runs it on its nested Buy accounts
+pub fn process(
+    ctx: Context<Buy>,
+    operation: TradeOperation,
+    base_input_amount: u64,
+    expected_amount: u64
+) -> Result<()> {
+    let clock = Clock::get()?;
+    ctx.accounts.price_observation
+        .load_mut()?
+        .update(clock.unix_timestamp as u64, ctx.accounts.bonding_curve.price_x32()?);
+
+    let swap = ctx.accounts.bonding_curve.swap_buy(
+        &ctx.accounts.base_token_configuration,
+        base_input_amount,
+        |amount| get_transfer_fee(&ctx.accounts.base_token_mint, amount)
+    )?;
+    require!(swap.amount_out >= expected_amount, CustomError::SlippageExceeded);
+
+    let referral_fee_share = match ctx.accounts.referrer {
+        Some(_) => ctx.accounts.global_configuration.referral_fee_share,
+        None => 0,
+    };
+    let fees = ctx.accounts.bonding_curve.split_platform_fee(swap.platform_fee, referral_fee_share)?;
+
+    let is_native = is_native_mint(&ctx.accounts.base_token_mint.key());
+    let unwrap = is_native && ctx.accounts.user_base_token_ata.amount == 0;
+    if is_native {
+        wrap_sol(
+            &ctx.accounts.payer,
+            &ctx.accounts.user_base_token_ata,
+            &ctx.accounts.system_program,
+            &ctx.accounts.token_program,
+            base_input_amount
+        )?;
+    }
 
     // Transfer fee to the fee account
+    ctx.accounts.transfer_from_user(&ctx.accounts.protocol_fee_vault_ata, fees.protocol_fee)?;
+    ctx.accounts.transfer_from_user(&ctx.accounts.creator_vault_base_token_ata, fees.creator_fee)?;
 
-    // Transfer base tokens from user to pool
+    if let Some(referrer) = &ctx.accounts.referrer {
+        require!(referrer.wallet != ctx.accounts.payer.key(), CustomError::SelfReferral);
+        let referrer_base_token_ata = ctx.accounts.referrer_base_token_ata
+            .as_ref()
+            .ok_or(CustomError::InvalidReferrer)?;
+        referrer.validate_payout_account(referrer_base_token_ata, ctx.accounts.base_token_mint.key())?;
+        ctx.accounts.transfer_from_user(referrer_base_token_ata, fees.referral_fee)?;
+    }
+    if let Some(referrer) = &mut ctx.accounts.referrer {
+        referrer.record(fees.referral_fee)?;
+    }
 
-    bonding_curve.base_token_reserves += base_input_amount - platform_fee;
-    bonding_curve.quote_token_reserves -= estimated_out_quote;
+    // Transfer base tokens from user to pool and quote tokens from pool to user
+    ctx.accounts.transfer_from_user(&ctx.accounts.base_token_pool, swap.pool_amount_in)?;
+    ctx.accounts.transfer_from_pool(swap.pool_amount_out, ctx.bumps.pool)?;
 
-    emit!(TransactionEvent {
-        operation: "Buy".to_string(),
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.base_token_reserves = swap.after.base_token_reserves;
+    bonding_curve.quote_token_reserves = swap.after.quote_token_reserves;
+    let sequence = ctx.accounts.pool.record_trade(true, base_input_amount, swap.platform_fee)?;
+
+    ctx.accounts.quote_token_pool.reload()?;
+    let reserves = bonding_curve.reserves(ctx.accounts.quote_token_pool.amount)?;
+    let event = TransactionEvent {
+        operation,
         creator: ctx.accounts.payer.key(),
         input_amount: base_input_amount,
-        output_amount: estimated_out_quote,
-        platform_fee: platform_fee,
+        output_amount: swap.amount_out,
+        platform_fee: swap.platform_fee,
         base_token_mint: ctx.accounts.base_token_mint.key(),
         mint_address: ctx.accounts.mint_address.key(),
-        fee_wallet: ctx.accounts.fee_account.key(),
-    });
-
-    if
-        bonding_curve.base_token_reserves + bonding_curve.init_virtual_base_token >=
-        ctx.accounts.global_configuration.bonding_curve_limitation
-    {
-        emit!(BondingCurveCompleted {
+        fee_wallet: ctx.accounts.protocol_fee_vault.key(),
+        referrer: ctx.accounts.referrer.as_ref().map(|referrer| referrer.wallet),
+        virtual_base_reserves: reserves.virtual_base,
+        virtual_quote_reserves: reserves.virtual_quote,
+        real_base_reserves: reserves.real_base,
+        real_quote_reserves: reserves.real_quote,
+        price_x32: bonding_curve.price_x32()?,
+        timestamp: clock.unix_timestamp,
+        slot: clock.slot,
+        sequence,
+    };
+    #[cfg(feature = "log-events")]
+    emit!(event);
+    #[cfg(not(feature = "log-events"))]
+    emit_cpi!(event);
+
+    if bonding_curve.is_limitation_reached(ctx.accounts.base_token_configuration.bonding_curve_limitation) {
+        let event = BondingCurveCompleted {
             mint_address: ctx.accounts.mint_address.key(),
             user_quote_token_ata: ctx.accounts.user_quote_token_ata.key(),
             pool: ctx.accounts.pool.key(),
             quote_token_pool: ctx.accounts.quote_token_pool.key(),
-        });
+        };
+        #[cfg(feature = "log-events")]
+        emit!(event);
+        #[cfg(not(feature = "log-events"))]
+        emit_cpi!(event);
 
         bonding_curve.is_completed = true;
     }
+
+    if unwrap {
+        unwrap_sol(&ctx.accounts.payer, &ctx.accounts.user_base_token_ata, &ctx.accounts.token_program)?;
+    }
     Ok(())
 }
+
+impl<'info> Buy<'info> {
+    fn transfer_from_pool(&self, amount: u64, pool_bump: u8) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let mint_bytes = self.mint_address.key().to_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[&mint_bytes, POOL_SEED.as_bytes(), &[pool_bump]]];
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.quote_token_program.to_account_info(),
+                TransferChecked {
+                    from: self.quote_token_pool.to_account_info(),
+                    mint: self.mint_address.to_account_info(),
+                    to: self.user_quote_token_ata.to_account_info(),
+                    authority: self.pool.to_account_info(),
+                },
+                signer_seeds
+            ),
+            amount,
+            self.mint_address.decimals
+        )
+    }
+
+    fn transfer_from_user(
+        &self,
+        to: &InterfaceAccount<'info, TokenInterAccount>,
+        amount: u64
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        transfer_checked(
+            CpiContext::new(self.token_program.to_account_info(), TransferChecked {
+                from: self.user_base_token_ata.to_account_info(),
+                mint: self.base_token_mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: self.payer.to_account_info(),
+            }),
+            amount,
+            self.base_token_mint.decimals
+        )
+    }
+}
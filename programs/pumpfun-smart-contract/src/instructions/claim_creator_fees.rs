@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        transfer_checked,
+        Mint as InterfaceMint,
+        TokenAccount as TokenInterAccount,
+        TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::consts::*;
+use crate::errors::CustomError;
+use crate::events::CreatorFeesClaimed;
+use crate::state::Pool;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(
+      seeds = [&mint_address.key().to_bytes(), POOL_SEED.as_bytes()],
+      bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub mint_address: InterfaceAccount<'info, InterfaceMint>,
+
+    pub base_token_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    ///CHECK:
+    #[account(
+      seeds = [&mint_address.key().to_bytes(), CREATOR_VAULT_SEED.as_bytes()],
+      bump
+    )]
+    pub creator_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = creator_vault,
+        associated_token::token_program = token_program
+    )]
+    pub creator_vault_base_token_ata: InterfaceAccount<'info, TokenInterAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = token_program
+    )]
+    pub creator_base_token_ata: InterfaceAccount<'info, TokenInterAccount>,
+
+    #[account(
+        mut,
+        constraint = creator.key() == pool.creator @ CustomError::InvalidCreator
+    )]
+    pub creator: Signer<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+    let amount = ctx.accounts.creator_vault_base_token_ata.amount;
+    require!(amount > 0, CustomError::NothingToClaim);
+
+    let mint_bytes = ctx.accounts.mint_address.key().to_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[
+        &[&mint_bytes, CREATOR_VAULT_SEED.as_bytes(), &[ctx.bumps.creator_vault]],
+    ];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.creator_vault_base_token_ata.to_account_info(),
+                mint: ctx.accounts.base_token_mint.to_account_info(),
+                to: ctx.accounts.creator_base_token_ata.to_account_info(),
+                authority: ctx.accounts.creator_vault.to_account_info(),
+            },
+            signer_seeds
+        ),
+        amount,
+        ctx.accounts.base_token_mint.decimals
+    )?;
+
+    let event = CreatorFeesClaimed {
+        mint_address: ctx.accounts.mint_address.key(),
+        creator: ctx.accounts.creator.key(),
+        amount,
+    };
+    #[cfg(feature = "log-events")]
+    emit!(event);
+    #[cfg(not(feature = "log-events"))]
+    emit_cpi!(event);
+
+    Ok(())
+}
@@ -2,41 +2,76 @@ use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{
     system_instruction,
     program::invoke,
-    entrypoint::ProgramResult,
     program_pack::Pack,
 };
 use anchor_spl::{
     associated_token::{
-        spl_associated_token_account::instruction::create_associated_token_account,
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent,
         AssociatedToken,
     },
-    token::{
+    token::spl_token,
+    token_2022::spl_token_2022::{
         self,
+        extension::ExtensionType,
+        instruction::AuthorityType,
+        state::Mint as Token2022Mint,
+    },
+    token_2022_extensions::spl_token_metadata_interface::state::TokenMetadata,
+    token_interface::{
         initialize_mint,
+        metadata_pointer_initialize,
         mint_to,
-        spl_token,
+        set_authority,
+        token_metadata_initialize,
+        transfer_checked,
         InitializeMint,
-        Token,
-        Mint,
+        MetadataPointerInitialize,
+        Mint as InterfaceMint,
         MintTo,
-        TokenAccount,
-        Transfer,
+        SetAuthority,
+        TokenAccount as TokenInterAccount,
+        TokenInterface,
+        TokenMetadataInitialize,
+        TransferChecked,
     },
     metadata::{ mpl_token_metadata, create_metadata_accounts_v3, Metadata },
 };
 use mpl_token_metadata::types::DataV2;
 use anchor_spl::metadata::CreateMetadataAccountsV3;
 
-use crate::state::{ InitializeConfiguration, BondingCurve };
+use crate::state::{
+    InitializeConfiguration,
+    BaseTokenConfiguration,
+    BondingCurve,
+    ObservationState,
+    Pool,
+    PresaleConfig,
+};
 use crate::consts::*;
 use crate::errors::CustomError;
 use crate::events::*;
+use crate::utils::{
+    get_transfer_fee,
+    is_native_mint,
+    token_account_amount,
+    unwrap_sol,
+    validate_token_metadata,
+    wrap_sol,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CreatePool<'info> {
     #[account(mut, seeds = [CONFIG_SEED.as_bytes()], bump)]
     pub global_configuration: Account<'info, InitializeConfiguration>,
 
+    /// Selects the base token the pool is launched against
+    #[account(
+        seeds = [BASE_TOKEN_CONFIG_SEED.as_bytes(), &base_token_mint.key().to_bytes()],
+        bump
+    )]
+    pub base_token_configuration: Account<'info, BaseTokenConfiguration>,
+
     #[account(
         init,
         payer = payer,
@@ -46,11 +81,21 @@ pub struct CreatePool<'info> {
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
 
-    /// CHECK:
+    #[account(
+        init,
+        payer = payer,
+        seeds = [&mint_address.key().to_bytes(), OBSERVATION_SEED.as_bytes()],
+        space = ObservationState::LEN,
+        bump
+    )]
+    pub price_observation: AccountLoader<'info, ObservationState>,
+
+    /// CHECK: Either a fresh keypair signing the transaction, or the PDA derived from
+    /// the payer and `mint_nonce`; verified in the handler
     #[account(mut)]
-    pub mint_address: Signer<'info>,
+    pub mint_address: UncheckedAccount<'info>,
 
-    pub base_token_mint: Account<'info, Mint>,
+    pub base_token_mint: InterfaceAccount<'info, InterfaceMint>,
 
     #[account(
         init_if_needed,
@@ -59,15 +104,20 @@ pub struct CreatePool<'info> {
         associated_token::authority = payer,
         associated_token::token_program = token_program
     )]
-    pub user_base_token_ata: Account<'info, TokenAccount>,
+    pub user_base_token_ata: InterfaceAccount<'info, TokenInterAccount>,
 
     /// CHECK:
     #[account(mut)]
     pub user_quote_token_ata: AccountInfo<'info>,
 
-    /// CHECK:
-    #[account(mut, seeds = [&mint_address.key().to_bytes(), POOL_SEED.as_bytes()], bump)]
-    pub pool: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [&mint_address.key().to_bytes(), POOL_SEED.as_bytes()],
+        space = 8 + Pool::SIZE,
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
 
     /// CHECK:
     #[account(
@@ -77,43 +127,62 @@ pub struct CreatePool<'info> {
         associated_token::authority = pool,
         associated_token::token_program = token_program
     )]
-    pub base_token_pool: Account<'info, TokenAccount>,
+    pub base_token_pool: InterfaceAccount<'info, TokenInterAccount>,
 
     /// CHECK:
     #[account(mut)]
     pub quote_token_pool: AccountInfo<'info>,
 
-    /// CHECK: Validate address by deriving pda
-    #[account(
-        mut,
-        seeds = [b"metadata", token_metadata_program.key().as_ref(), mint_address.key().as_ref()],
-        bump,
-        seeds::program = token_metadata_program.key(),
-    )]
-    pub metadata: UncheckedAccount<'info>,
+    /// CHECK: Metaplex metadata account, only for spl-token mints. The metadata program checks
+    /// its address when creating it
+    #[account(mut)]
+    pub metadata: Option<UncheckedAccount<'info>>,
 
     /// CHECK:
     #[account(mut)]
     pub fee_account: AccountInfo<'info>,
 
+    /// CHECK:
+    #[account(
+        seeds = [PROTOCOL_FEE_VAULT_SEED.as_bytes(), &base_token_mint.key().to_bytes()],
+        bump
+    )]
+    pub protocol_fee_vault: AccountInfo<'info>,
+
     #[account(
-        mut,
+        init_if_needed,
+        payer = payer,
         associated_token::mint = base_token_mint,
-        associated_token::authority = fee_account,
+        associated_token::authority = protocol_fee_vault,
         associated_token::token_program = token_program
     )]
-    pub fee_base_token_ata: Account<'info, TokenAccount>,
+    pub protocol_fee_vault_ata: InterfaceAccount<'info, TokenInterAccount>,
 
     /// CHECK:
     #[account(mut)]
     pub fee_quote_token_ata: AccountInfo<'info>,
 
+    /// CHECK:
+    #[account(seeds = [&mint_address.key().to_bytes(), CREATOR_VAULT_SEED.as_bytes()], bump)]
+    pub creator_vault: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = creator_vault,
+        associated_token::token_program = token_program
+    )]
+    pub creator_vault_base_token_ata: InterfaceAccount<'info, TokenInterAccount>,
+
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    pub token_metadata_program: Program<'info, Metadata>,
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Token program owning the launched mint, spl-token or Token-2022
+    pub quote_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -126,108 +195,191 @@ pub fn handler(
     symbol: String,
     uri: String,
     dev_buy_amount: u64,
-    initial_token_transfer_percent: u64
+    initial_token_transfer_percent: u64,
+    presale: Option<PresaleConfig>,
+    use_token_2022: bool,
+    mint_nonce: Option<u64>
 ) -> Result<()> {
+    validate_token_metadata(&name, &symbol, &uri)?;
+
+    let payer_key = ctx.accounts.payer.key();
+    let mint_nonce_bytes = mint_nonce.unwrap_or_default().to_le_bytes();
+    let mint_bump = match mint_nonce {
+        Some(_) => {
+            let (expected_mint, bump) = Pubkey::find_program_address(
+                &[MINT_SEED.as_bytes(), &payer_key.to_bytes(), &mint_nonce_bytes],
+                ctx.program_id
+            );
+            require_keys_eq!(
+                expected_mint,
+                ctx.accounts.mint_address.key(),
+                CustomError::InvalidMintAddress
+            );
+            bump
+        }
+        None => {
+            require!(ctx.accounts.mint_address.is_signer, CustomError::InvalidMintAddress);
+            0
+        }
+    };
+
+    let expected_quote_token_program = if use_token_2022 { spl_token_2022::ID } else { spl_token::ID };
     require!(
-        ctx.accounts.base_token_mint.key() == ctx.accounts.global_configuration.base_token_ca,
-        CustomError::InvalidBaseToken
+        ctx.accounts.quote_token_program.key() == expected_quote_token_program,
+        CustomError::InvalidTokenProgram
     );
+    if let Some(presale) = presale {
+        require!(
+            presale.end_time > Clock::get()?.unix_timestamp,
+            CustomError::InvalidPresaleEndTime
+        );
+    }
     require!(
         ctx.accounts.fee_account.key() == ctx.accounts.global_configuration.fee_wallet,
         CustomError::InvalidFeeWallet
     );
+    let required_base_token = dev_buy_amount
+        .checked_add(ctx.accounts.base_token_configuration.create_pool_fee_amount)
+        .ok_or(CustomError::MathOverflow)?;
+    let is_native = is_native_mint(&ctx.accounts.base_token_mint.key());
+    let unwrap = is_native && ctx.accounts.user_base_token_ata.amount == 0;
+    if is_native {
+        wrap_sol(
+            &ctx.accounts.payer,
+            &ctx.accounts.user_base_token_ata,
+            &ctx.accounts.system_program,
+            &ctx.accounts.token_program,
+            required_base_token
+        )?;
+        ctx.accounts.user_base_token_ata.reload()?;
+    }
     require!(
-        ctx.accounts.user_base_token_ata.amount >
-            dev_buy_amount + ctx.accounts.global_configuration.create_pool_fee_amount,
+        ctx.accounts.user_base_token_ata.amount >= required_base_token,
         CustomError::NotEnoughBaseToken
     );
     require!(
-        initial_token_transfer_percent >= 0 && initial_token_transfer_percent <= 10000,
+        initial_token_transfer_percent <= 10000,
         CustomError::InvalidInitialTokenTransferPercent
     );
 
-    let space = spl_token::state::Mint::LEN as u64;
-    let metadata_space = 250;
-
-    let lamports_required = Rent::get()?.minimum_balance((space + metadata_space) as usize);
+    // Token-2022 keeps the metadata inside the mint, Metaplex allocates its own account
+    let (space, embedded_metadata_space) = if use_token_2022 {
+        let token_metadata = TokenMetadata {
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            ..Default::default()
+        };
+        (
+            ExtensionType::try_calculate_account_len::<Token2022Mint>(
+                &[ExtensionType::MetadataPointer]
+            )? as u64,
+            token_metadata.tlv_size_of()? as u64,
+        )
+    } else {
+        (spl_token::state::Mint::LEN as u64, 0)
+    };
+
+    let rent = Rent::get()?;
+    let mint_account_space = space.checked_add(embedded_metadata_space).ok_or(CustomError::MathOverflow)?;
+    let lamports_required = rent.minimum_balance(mint_account_space as usize);
+    let metadata_lamports = if use_token_2022 {
+        0
+    } else {
+        rent.minimum_balance(METADATA_ACCOUNT_SIZE)
+    };
 
-    require!(ctx.accounts.payer.lamports() > lamports_required, CustomError::NotEnoughSolBalance);
+    require!(
+        ctx.accounts.payer.lamports() >
+            lamports_required.checked_add(metadata_lamports).ok_or(CustomError::MathOverflow)?,
+        CustomError::NotEnoughSolBalance
+    );
 
     let ix = system_instruction::create_account(
         &ctx.accounts.payer.key(),
         &ctx.accounts.mint_address.key(),
         lamports_required,
         space,
-        &spl_token::id()
+        &ctx.accounts.quote_token_program.key()
     );
-    anchor_lang::solana_program::program::invoke(
+    let payer_bytes = payer_key.to_bytes();
+    let mint_signer_seeds: &[&[&[u8]]] = &[
+        &[MINT_SEED.as_bytes(), &payer_bytes, &mint_nonce_bytes, &[mint_bump]],
+    ];
+    anchor_lang::solana_program::program::invoke_signed(
         &ix,
         &[
             ctx.accounts.payer.to_account_info(),
             ctx.accounts.mint_address.to_account_info(),
             ctx.accounts.system_program.to_account_info(),
-        ]
+        ],
+        if mint_nonce.is_some() { mint_signer_seeds } else { &[] }
     )?;
 
+    if use_token_2022 {
+        // The mint stores its own metadata, the pool PDA keeps authority over it
+        metadata_pointer_initialize(
+            CpiContext::new(
+                ctx.accounts.quote_token_program.to_account_info(),
+                MetadataPointerInitialize {
+                    token_program_id: ctx.accounts.quote_token_program.to_account_info(),
+                    mint: ctx.accounts.mint_address.to_account_info(),
+                }
+            ),
+            Some(ctx.accounts.pool.key()),
+            Some(ctx.accounts.mint_address.key())
+        )?;
+    }
+
     initialize_mint(
-        CpiContext::new(ctx.accounts.token_program.to_account_info(), InitializeMint {
+        CpiContext::new(ctx.accounts.quote_token_program.to_account_info(), InitializeMint {
             mint: ctx.accounts.mint_address.to_account_info(),
             rent: ctx.accounts.rent.to_account_info(),
         }),
-        6,
+        QUOTE_TOKEN_DECIMALS,
         &ctx.accounts.payer.key(),
         Some(&ctx.accounts.payer.key())
     )?;
 
-    ctx.accounts.initialize_token_metadata(name.clone(), symbol.clone(), uri.clone())?;
-
-    //create user quote token ata
-
-    // create quote token ata of the pool
+    if use_token_2022 {
+        ctx.accounts.initialize_token_2022_metadata(name.clone(), symbol.clone(), uri.clone())?;
+    } else {
+        ctx.accounts.initialize_token_metadata(name.clone(), symbol.clone(), uri.clone())?;
+    }
 
-    //create fee wallet quote token ata
-    invoke(
-        &create_associated_token_account(
-            &ctx.accounts.payer.key(),
-            &ctx.accounts.fee_account.key(),
-            &ctx.accounts.mint_address.key(),
-            &ctx.accounts.token_program.key
-        ),
-        &[
-            ctx.accounts.payer.to_account_info(),
-            ctx.accounts.fee_quote_token_ata.to_account_info(),
-            ctx.accounts.fee_account.to_account_info(),
-            ctx.accounts.mint_address.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-            ctx.accounts.token_program.to_account_info(),
-            ctx.accounts.associated_token_program.to_account_info(),
-        ]
+    ctx.accounts.create_quote_token_ata(
+        &ctx.accounts.user_quote_token_ata,
+        &ctx.accounts.payer.to_account_info()
     )?;
+    ctx.accounts.create_quote_token_ata(
+        &ctx.accounts.quote_token_pool,
+        &ctx.accounts.pool.to_account_info()
+    )?;
+    ctx.accounts.create_quote_token_ata(&ctx.accounts.fee_quote_token_ata, &ctx.accounts.fee_account)?;
 
-    let token_supply = ctx.accounts.global_configuration.initial_virtual_quote_token;
+    let token_supply = ctx.accounts.base_token_configuration.initial_virtual_quote_token;
 
-    ctx.accounts.transfer_fee_to_fee_account()?;
-    ctx.accounts.mint_tokens(token_supply)?;
-
-    let base = u128::from(ctx.accounts.global_configuration.initial_virtual_base_token);
-    let quote = u128::from(ctx.accounts.global_configuration.initial_virtual_quote_token);
+    let base = u128::from(ctx.accounts.base_token_configuration.initial_virtual_base_token);
+    let quote = u128::from(ctx.accounts.base_token_configuration.initial_virtual_quote_token);
 
     let k_value = base.checked_mul(quote).ok_or(CustomError::MathOverflow)?;
 
-    let create_pool_fee = ctx.accounts.global_configuration.create_pool_fee_amount;
-    let dev_buy_fee = (dev_buy_amount * ctx.accounts.global_configuration.swap_fee.clone()) / 10000;
+    let create_pool_fee = ctx.accounts.base_token_configuration.create_pool_fee_amount;
+    let dev_buy_fee = ctx.accounts.base_token_configuration.platform_fee(dev_buy_amount)?;
+    let total_fee = create_pool_fee.checked_add(dev_buy_fee).ok_or(CustomError::MathOverflow)?;
 
     let initial_base_token = u128::from(
-        ctx.accounts.global_configuration.initial_virtual_base_token
+        ctx.accounts.base_token_configuration.initial_virtual_base_token
     );
-    let quote_token = u128::from(ctx.accounts.global_configuration.initial_virtual_quote_token);
-    let dev_buy_amount = u128::from(dev_buy_amount);
-    let dev_buy_fee = u128::from(dev_buy_fee);
+    // The pool only receives what is left after the base mint's transfer fee
+    let pool_input_amount = dev_buy_amount.checked_sub(dev_buy_fee).ok_or(CustomError::MathUnderflow)?;
+    let received_input_amount = pool_input_amount
+        .checked_sub(get_transfer_fee(&ctx.accounts.base_token_mint, pool_input_amount)?)
+        .ok_or(CustomError::MathUnderflow)?;
 
     let base_sum = initial_base_token
-        .checked_add(dev_buy_amount)
-        .and_then(|v| v.checked_sub(dev_buy_fee))
-        .ok_or(CustomError::MathUnderflow)?;
+        .checked_add(u128::from(received_input_amount))
+        .ok_or(CustomError::MathOverflow)?;
 
     let division = k_value.checked_div(base_sum).ok_or(CustomError::MathDivisionByZero)?;
 
@@ -235,48 +387,271 @@ pub fn handler(
 
     let quote_amount = u64::try_from(quote_amount_u128).map_err(|_| CustomError::MathOverflow)?;
 
-    let initial_token_transfer_amount = quote_amount
-        .checked_mul(initial_token_transfer_percent)
-        .and_then(|v| v.checked_div(10_000))
-        .and_then(|v| u64::try_from(v).ok())
-        .ok_or(CustomError::MathOverflow)?;
-
-    // Dev buy Instruction
+    ctx.accounts.transfer_fee_to_fee_account(total_fee)?;
+    ctx.accounts.mint_tokens(token_supply)?;
 
-    emit!(TransactionEvent {
-        operation: "Dev Buy".to_string(),
-        creator: ctx.accounts.payer.key(),
-        input_amount: dev_buy_amount as u64,
-        output_amount: quote_amount,
-        platform_fee: create_pool_fee + (dev_buy_fee as u64),
-        base_token_mint: ctx.accounts.base_token_mint.key(),
-        mint_address: ctx.accounts.mint_address.key(),
-        fee_wallet: ctx.accounts.fee_account.key(),
-    });
+    // Dev buy
+    ctx.accounts.transfer_to_pool(pool_input_amount)?;
+    ctx.accounts.transfer_from_pool(quote_amount, ctx.bumps.pool)?;
 
     // authority
     ctx.accounts.set_freeze_authority()?;
     ctx.accounts.set_mint_authority()?;
 
     // Clone the needed values before mutably borrowing bonding_curve
-    let initial_virtual_base_token = ctx.accounts.global_configuration.initial_virtual_base_token;
-    let initial_virtual_quote_token = ctx.accounts.global_configuration.initial_virtual_quote_token;
+    let initial_virtual_base_token = ctx.accounts.base_token_configuration.initial_virtual_base_token;
+    let initial_virtual_quote_token = ctx.accounts.base_token_configuration.initial_virtual_quote_token;
+
+    let clock = Clock::get()?;
+    let pool = &mut ctx.accounts.pool;
+
+    pool.creator = ctx.accounts.payer.key();
+    pool.mint_address = ctx.accounts.mint_address.key();
+    pool.base_token_mint = ctx.accounts.base_token_mint.key();
+    pool.bump = ctx.bumps.pool;
+    pool.bonding_curve_bump = ctx.bumps.bonding_curve;
+    pool.creator_vault_bump = ctx.bumps.creator_vault;
+    pool.created_at = clock.unix_timestamp;
+    pool.created_slot = clock.slot;
+    let sequence = pool.record_trade(true, dev_buy_amount, total_fee)?;
+
+    let mut price_observation = ctx.accounts.price_observation.load_init()?;
+    price_observation.mint_address = ctx.accounts.mint_address.key();
+    price_observation.update(clock.unix_timestamp as u64, 0);
+    drop(price_observation);
 
     let bonding_curve = &mut ctx.accounts.bonding_curve;
 
+    bonding_curve.init_virtual_base_token = initial_virtual_base_token;
+    bonding_curve.init_virtual_quote_token = initial_virtual_quote_token;
+    bonding_curve.k_value = k_value;
+    let base_token_reserves = base_sum
+        .checked_sub(initial_base_token)
+        .ok_or(CustomError::MathUnderflow)?;
+    bonding_curve.base_token_reserves = u64::try_from(base_token_reserves).map_err(
+        |_| CustomError::MathOverflow
+    )?;
+    bonding_curve.quote_token_reserves = initial_virtual_quote_token
+        .checked_sub(quote_amount)
+        .ok_or(CustomError::MathUnderflow)?;
+    bonding_curve.creator_fee_share = ctx.accounts.global_configuration.creator_fee_share;
+
+    let reserves = bonding_curve.reserves(token_account_amount(&ctx.accounts.quote_token_pool)?)?;
+    let event = TransactionEvent {
+        operation: TradeOperation::DevBuy,
+        creator: ctx.accounts.payer.key(),
+        input_amount: dev_buy_amount,
+        output_amount: quote_amount,
+        platform_fee: total_fee,
+        base_token_mint: ctx.accounts.base_token_mint.key(),
+        mint_address: ctx.accounts.mint_address.key(),
+        fee_wallet: ctx.accounts.protocol_fee_vault.key(),
+        referrer: None,
+        virtual_base_reserves: reserves.virtual_base,
+        virtual_quote_reserves: reserves.virtual_quote,
+        real_base_reserves: reserves.real_base,
+        real_quote_reserves: reserves.real_quote,
+        price_x32: bonding_curve.price_x32()?,
+        timestamp: clock.unix_timestamp,
+        slot: clock.slot,
+        sequence,
+    };
+    #[cfg(feature = "log-events")]
+    emit!(event);
+    #[cfg(not(feature = "log-events"))]
+    emit_cpi!(event);
+
+    if let Some(presale) = presale {
+        bonding_curve.presale_merkle_root = presale.merkle_root;
+        bonding_curve.presale_end_time = presale.end_time;
+    }
+
     if
-        bonding_curve.base_token_reserves + bonding_curve.init_virtual_base_token >=
-        ctx.accounts.global_configuration.bonding_curve_limitation
+        bonding_curve.is_limitation_reached(
+            ctx.accounts.base_token_configuration.bonding_curve_limitation
+        )
     {
-        emit!(BondingCurveCompleted {
+        let event = BondingCurveCompleted {
             mint_address: ctx.accounts.mint_address.key(),
             user_quote_token_ata: ctx.accounts.user_quote_token_ata.key(),
             pool: ctx.accounts.pool.key(),
             quote_token_pool: ctx.accounts.quote_token_pool.key(),
-        });
+        };
+        #[cfg(feature = "log-events")]
+        emit!(event);
+        #[cfg(not(feature = "log-events"))]
+        emit_cpi!(event);
 
         bonding_curve.is_completed = true;
     }
 
+    if unwrap {
+        unwrap_sol(&ctx.accounts.payer, &ctx.accounts.user_base_token_ata, &ctx.accounts.token_program)?;
+    }
+
     Ok(())
 }
+
+impl<'info> CreatePool<'info> {
+    // The pool PDA is the update authority so the creator can fix metadata until migration
+    fn initialize_token_metadata(&self, name: String, symbol: String, uri: String) -> Result<()> {
+        let metadata = self.metadata.as_ref().ok_or(CustomError::MissingMetadataAccounts)?;
+        let token_metadata_program = self.token_metadata_program
+            .as_ref()
+            .ok_or(CustomError::MissingMetadataAccounts)?;
+
+        create_metadata_accounts_v3(
+            CpiContext::new(
+                token_metadata_program.to_account_info(),
+                CreateMetadataAccountsV3 {
+                    metadata: metadata.to_account_info(),
+                    mint: self.mint_address.to_account_info(),
+                    mint_authority: self.payer.to_account_info(),
+                    payer: self.payer.to_account_info(),
+                    update_authority: self.pool.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                    rent: self.rent.to_account_info(),
+                }
+            ),
+            DataV2 {
+                name,
+                symbol,
+                uri,
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            },
+            true,
+            false,
+            None
+        )
+    }
+
+    fn initialize_token_2022_metadata(
+        &self,
+        name: String,
+        symbol: String,
+        uri: String
+    ) -> Result<()> {
+        token_metadata_initialize(
+            CpiContext::new(self.quote_token_program.to_account_info(), TokenMetadataInitialize {
+                program_id: self.quote_token_program.to_account_info(),
+                mint: self.mint_address.to_account_info(),
+                metadata: self.mint_address.to_account_info(),
+                mint_authority: self.payer.to_account_info(),
+                update_authority: self.pool.to_account_info(),
+            }),
+            name,
+            symbol,
+            uri
+        )
+    }
+
+    // Idempotent because the payer's and the fee wallet's accounts are the same when the fee
+    // wallet launches a token
+    fn create_quote_token_ata(&self, ata: &AccountInfo<'info>, owner: &AccountInfo<'info>) -> Result<()> {
+        invoke(
+            &create_associated_token_account_idempotent(
+                &self.payer.key(),
+                &owner.key(),
+                &self.mint_address.key(),
+                &self.quote_token_program.key()
+            ),
+            &[
+                self.payer.to_account_info(),
+                ata.clone(),
+                owner.clone(),
+                self.mint_address.to_account_info(),
+                self.system_program.to_account_info(),
+                self.quote_token_program.to_account_info(),
+                self.associated_token_program.to_account_info(),
+            ]
+        )?;
+        Ok(())
+    }
+
+    fn transfer_fee_to_fee_account(&self, amount: u64) -> Result<()> {
+        self.transfer_base_token(&self.protocol_fee_vault_ata, amount)
+    }
+
+    fn transfer_to_pool(&self, amount: u64) -> Result<()> {
+        self.transfer_base_token(&self.base_token_pool, amount)
+    }
+
+    fn transfer_base_token(
+        &self,
+        to: &InterfaceAccount<'info, TokenInterAccount>,
+        amount: u64
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        transfer_checked(
+            CpiContext::new(self.token_program.to_account_info(), TransferChecked {
+                from: self.user_base_token_ata.to_account_info(),
+                mint: self.base_token_mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: self.payer.to_account_info(),
+            }),
+            amount,
+            self.base_token_mint.decimals
+        )
+    }
+
+    // The whole supply starts in the pool, the payer is still the mint authority here
+    fn mint_tokens(&self, amount: u64) -> Result<()> {
+        mint_to(
+            CpiContext::new(self.quote_token_program.to_account_info(), MintTo {
+                mint: self.mint_address.to_account_info(),
+                to: self.quote_token_pool.to_account_info(),
+                authority: self.payer.to_account_info(),
+            }),
+            amount
+        )
+    }
+
+    // Sends the dev buy's tokens from the pool to the payer
+    fn transfer_from_pool(&self, amount: u64, pool_bump: u8) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let mint_bytes = self.mint_address.key().to_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[&mint_bytes, POOL_SEED.as_bytes(), &[pool_bump]]];
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.quote_token_program.to_account_info(),
+                TransferChecked {
+                    from: self.quote_token_pool.to_account_info(),
+                    mint: self.mint_address.to_account_info(),
+                    to: self.user_quote_token_ata.to_account_info(),
+                    authority: self.pool.to_account_info(),
+                },
+                signer_seeds
+            ),
+            amount,
+            QUOTE_TOKEN_DECIMALS
+        )
+    }
+
+    // Revoking both authorities fixes the supply and keeps every holder able to trade
+    fn set_freeze_authority(&self) -> Result<()> {
+        self.revoke_authority(AuthorityType::FreezeAccount)
+    }
+
+    fn set_mint_authority(&self) -> Result<()> {
+        self.revoke_authority(AuthorityType::MintTokens)
+    }
+
+    fn revoke_authority(&self, authority_type: AuthorityType) -> Result<()> {
+        set_authority(
+            CpiContext::new(self.quote_token_program.to_account_info(), SetAuthority {
+                current_authority: self.payer.to_account_info(),
+                account_or_mint: self.mint_address.to_account_info(),
+            }),
+            authority_type,
+            None
+        )
+    }
+
+}
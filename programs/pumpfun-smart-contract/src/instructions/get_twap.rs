@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::consts::*;
+use crate::state::{ BondingCurve, ObservationState };
+
+#[derive(Accounts)]
+pub struct GetTwap<'info> {
+    #[account(
+      seeds = [&mint_address.key().to_bytes(), CURVE_SEED.as_bytes()],
+      bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+      seeds = [&mint_address.key().to_bytes(), OBSERVATION_SEED.as_bytes()],
+      bump
+    )]
+    pub price_observation: AccountLoader<'info, ObservationState>,
+
+    /// CHECK: Only used to derive the pool accounts
+    pub mint_address: UncheckedAccount<'info>,
+}
+
+// Returns the Q32 time weighted average price over the last `window` seconds
+pub fn handler(ctx: Context<GetTwap>, window: u64) -> Result<u128> {
+    let now = Clock::get()?.unix_timestamp as u64;
+    let current_price_x32 = ctx.accounts.bonding_curve.price_x32()?;
+
+    ctx.accounts.price_observation.load()?.twap_x32(now, window, current_price_x32)
+}
@@ -1,11 +1,27 @@
 pub mod initialize;
+pub mod add_base_token;
 pub mod create_pool;
 pub mod buy;
 pub mod sell;
 pub mod proxy_initialize;
+pub mod presale_buy;
+pub mod claim_creator_fees;
+pub mod register_referrer;
+pub mod withdraw_protocol_fees;
+pub mod update_token_metadata;
+pub mod get_twap;
+pub mod quote;
 
 pub use initialize::*;
+pub use add_base_token::*;
 pub use create_pool::*;
 pub use buy::*;
 pub use sell::*;
 pub use proxy_initialize::*;
+pub use presale_buy::*;
+pub use claim_creator_fees::*;
+pub use register_referrer::*;
+pub use withdraw_protocol_fees::*;
+pub use update_token_metadata::*;
+pub use get_twap::*;
+pub use quote::*;
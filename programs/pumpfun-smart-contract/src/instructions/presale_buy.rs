@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+use crate::consts::*;
+use crate::errors::CustomError;
+use crate::events::TradeOperation;
+use crate::instructions::buy::*;
+use crate::state::PresaleReceipt;
+
+#[derive(Accounts)]
+pub struct PresaleBuy<'info> {
+    pub buy: Buy<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = buy.payer,
+        seeds = [
+            &buy.mint_address.key().to_bytes(),
+            PRESALE_SEED.as_bytes(),
+            &buy.payer.key().to_bytes(),
+        ],
+        space = 8 + PresaleReceipt::SIZE,
+        bump
+    )]
+    pub presale_receipt: Account<'info, PresaleReceipt>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<PresaleBuy>,
+    base_input_amount: u64,
+    expected_amount: u64,
+    allocation: u64,
+    proof: Vec<[u8; 32]>
+) -> Result<()> {
+    let bonding_curve = &ctx.accounts.buy.bonding_curve;
+    require!(
+        bonding_curve.is_presale_active(Clock::get()?.unix_timestamp),
+        CustomError::PresaleIsNotActive
+    );
+
+    let user = ctx.accounts.buy.payer.key();
+    let leaf = keccak::hashv(&[user.as_ref(), &allocation.to_le_bytes()]).to_bytes();
+    require!(
+        verify_proof(&proof, bonding_curve.presale_merkle_root, leaf),
+        CustomError::InvalidPresaleProof
+    );
+
+    let receipt = &mut ctx.accounts.presale_receipt;
+    let purchased_amount = receipt.purchased_amount
+        .checked_add(base_input_amount)
+        .ok_or(CustomError::MathOverflow)?;
+    require!(purchased_amount <= allocation, CustomError::PresaleAllocationExceeded);
+
+    receipt.user = user;
+    receipt.mint_address = ctx.accounts.buy.mint_address.key();
+    receipt.purchased_amount = purchased_amount;
+
+    // Buy takes no remaining accounts
+    let buy_ctx = Context::new(ctx.program_id, &mut ctx.accounts.buy, &[], ctx.bumps.buy);
+    process(buy_ctx, TradeOperation::PresaleBuy, base_input_amount, expected_amount)
+}
+
+// Leaves are keccak(user || allocation_le), nodes hash their children in sorted order
+fn verify_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).to_bytes()
+        } else {
+            keccak::hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == root
+}
@@ -1,16 +1,28 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{ Token, Mint },
-    token_interface::{ Mint as InterfaceMint, TokenAccount as TokenInterAccount, TokenInterface },
+    metadata::{ update_metadata_accounts_v2, Metadata, UpdateMetadataAccountsV2 },
+    token::Token,
+    token_2022::spl_token_2022,
+    token_2022_extensions::spl_pod::optional_keys::OptionalNonZeroPubkey,
+    token_interface::{
+        token_metadata_update_authority,
+        transfer_checked,
+        Mint as InterfaceMint,
+        TokenAccount as TokenInterAccount,
+        TokenInterface,
+        TokenMetadataUpdateAuthority,
+        TransferChecked,
+    },
 };
 use raydium_cp_swap::{ cpi, program::RaydiumCpSwap, states::{ AmmConfig } };
 use anchor_lang::solana_program::system_instruction::transfer;
 use crate::errors::CustomError;
 use crate::consts::*;
-use crate::state::{ InitializeConfiguration, BondingCurve };
+use crate::state::{ InitializeConfiguration, BondingCurve, Pool };
 use crate::events::MigrationEvent;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ProxyInitialize<'info> {
     #[account(mut, seeds = [CONFIG_SEED.as_bytes()], bump)]
@@ -23,27 +35,50 @@ pub struct ProxyInitialize<'info> {
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
 
-    pub mint_address: Account<'info, Mint>,
+    #[account(mut)]
+    pub mint_address: InterfaceAccount<'info, InterfaceMint>,
 
-    ///CHECK:
     #[account(
       mut,
       seeds = [&mint_address.key().to_bytes(), POOL_SEED.as_bytes()],
-      bump
+      bump,
+      constraint = !pool.is_migrated() @ CustomError::AlreadyMigrated
+    )]
+    pub pool: Account<'info, Pool>,
+
+    /// Curve reserves of the base token, deposited into the Raydium pool
+    #[account(
+        mut,
+        constraint = base_token_pool.owner == pool.key() &&
+        base_token_pool.mint == pool.base_token_mint @ CustomError::TokenConstraintError
     )]
-    pub pool: AccountInfo<'info>,
+    pub base_token_pool: Box<InterfaceAccount<'info, TokenInterAccount>>,
+
+    /// Curve reserves of the launched token, deposited into the Raydium pool
+    #[account(
+        mut,
+        constraint = quote_token_pool.owner == pool.key() &&
+        quote_token_pool.mint == mint_address.key() @ CustomError::TokenConstraintError
+    )]
+    pub quote_token_pool: Box<InterfaceAccount<'info, TokenInterAccount>>,
 
     /// CHECK:
     #[account(
+        mut,
         constraint = migration_authority.key() == global_configuration.migration_authority.key() @ CustomError::InvalidMigrationAuth
     )]
     pub migration_authority: Signer<'info>,
 
     pub cp_swap_program: Program<'info, RaydiumCpSwap>,
 
-    /// CHECK: Address paying to create the pool. Can be anyone
-    #[account(mut)]
-    pub creator: AccountInfo<'info>,
+    /// Creates the Raydium pool and owns its liquidity. It holds no data so it can pay rent
+    /// through the system program, which the pool account can't
+    #[account(
+        mut,
+        seeds = [&mint_address.key().to_bytes(), LIQUIDITY_OWNER_SEED.as_bytes()],
+        bump
+    )]
+    pub liquidity_owner: SystemAccount<'info>,
 
     /// Which config the pool belongs to.
     pub amm_config: Box<Account<'info, AmmConfig>>,
@@ -58,25 +93,37 @@ pub struct ProxyInitialize<'info> {
 
     /// Token_0 mint, the key must smaller then token_1 mint.
     #[account(constraint = token_0_mint.key() < token_1_mint.key() @ CustomError::TokenConstraintError)]
-    pub token_0_mint: Account<'info, Mint>,
+    pub token_0_mint: Box<InterfaceAccount<'info, InterfaceMint>>,
 
     /// Token_1 mint, the key must grater then token_0 mint.
     #[account(mut)]
-    pub token_1_mint: Account<'info, Mint>,
+    pub token_1_mint: Box<InterfaceAccount<'info, InterfaceMint>>,
 
     /// CHECK: pool lp mint, init by cp-swap
     #[account(mut)]
     pub lp_mint: UncheckedAccount<'info>,
 
-    /// payer token0 account
-    #[account(mut)]
+    /// Liquidity owner token0 account, funded from the curve reserves
+    #[account(
+        init_if_needed,
+        payer = migration_authority,
+        associated_token::mint = token_0_mint,
+        associated_token::authority = liquidity_owner,
+        associated_token::token_program = token_0_program
+    )]
     pub creator_token_0: Box<InterfaceAccount<'info, TokenInterAccount>>,
 
-    /// creator token1 account
-    #[account(mut)]
+    /// Liquidity owner token1 account, funded from the curve reserves
+    #[account(
+        init_if_needed,
+        payer = migration_authority,
+        associated_token::mint = token_1_mint,
+        associated_token::authority = liquidity_owner,
+        associated_token::token_program = token_1_program
+    )]
     pub creator_token_1: Box<InterfaceAccount<'info, TokenInterAccount>>,
 
-    /// CHECK: creator lp ATA token account, init by cp-swap
+    /// CHECK: liquidity owner lp ATA token account, init by cp-swap
     #[account(mut)]
     pub creator_lp_token: UncheckedAccount<'info>,
 
@@ -108,6 +155,15 @@ pub struct ProxyInitialize<'info> {
     /// Program to create an ATA for receiving position NFT
     pub associated_token_program: Program<'info, AssociatedToken>,
 
+    /// CHECK: Metaplex metadata of the launched token, only for spl-token mints
+    #[account(mut)]
+    pub metadata: Option<UncheckedAccount<'info>>,
+
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
+
+    /// Token program owning the launched mint
+    pub quote_token_program: Interface<'info, TokenInterface>,
+
     /// Sysvar for program account
     pub rent: Sysvar<'info, Rent>,
 
@@ -121,9 +177,17 @@ pub fn handler(ctx: Context<ProxyInitialize>) -> Result<()> {
         CustomError::BondingCurveIsNotCompleted
     );
 
+    let base_is_token_0 = ctx.accounts.token_0_mint.key() == ctx.accounts.pool.base_token_mint;
+    let quote_mint = if base_is_token_0 { &ctx.accounts.token_1_mint } else { &ctx.accounts.token_0_mint };
+    require!(
+        quote_mint.key() == ctx.accounts.mint_address.key() &&
+            (base_is_token_0 || ctx.accounts.token_1_mint.key() == ctx.accounts.pool.base_token_mint),
+        CustomError::TokenConstraintError
+    );
+
     let fee: u64 = ctx.accounts.global_configuration.raydium_migration_fee;
 
-    let lamports_required = fee + tx_confirm_fee;
+    let lamports_required = fee.checked_add(TX_CONFIRM_FEE).ok_or(CustomError::MathOverflow)?;
 
     require!(
         ctx.accounts.migration_authority.lamports() > lamports_required,
@@ -132,7 +196,7 @@ pub fn handler(ctx: Context<ProxyInitialize>) -> Result<()> {
 
     let transfer_ix = transfer(
         &ctx.accounts.migration_authority.key(),
-        &ctx.accounts.creator.key(),
+        &ctx.accounts.liquidity_owner.key(),
         lamports_required
     );
 
@@ -140,32 +204,166 @@ pub fn handler(ctx: Context<ProxyInitialize>) -> Result<()> {
         &transfer_ix,
         &[
             ctx.accounts.migration_authority.to_account_info(),
-            ctx.accounts.creator.to_account_info(),
+            ctx.accounts.liquidity_owner.to_account_info(),
             ctx.accounts.system_program.to_account_info(),
         ]
     )?;
 
-    let (init_amount_0, init_amount_1) = if
-        ctx.accounts.token_0_mint.key() == ctx.accounts.global_configuration.base_token_ca
-    {
-        (
-            ctx.accounts.bonding_curve.base_token_reserves,
-            ctx.accounts.bonding_curve.quote_token_reserves,
-        )
-    } else {
-        (
-            ctx.accounts.bonding_curve.quote_token_reserves,
-            ctx.accounts.bonding_curve.base_token_reserves,
-        )
-    };
+    // The liquidity owner deposits the whole curve reserves, less any transfer fee on the way
+    ctx.accounts.withdraw_reserves(base_is_token_0, ctx.bumps.pool)?;
+    ctx.accounts.creator_token_0.reload()?;
+    ctx.accounts.creator_token_1.reload()?;
+    let init_amount_0 = ctx.accounts.creator_token_0.amount;
+    let init_amount_1 = ctx.accounts.creator_token_1.amount;
 
     // CPI to Raydium CP-Swap program to initialize the pool
+    ctx.accounts.initialize_raydium_pool(init_amount_0, init_amount_1, ctx.bumps.liquidity_owner)?;
 
-    emit!(MigrationEvent {
+    ctx.accounts.lock_token_metadata(ctx.bumps.pool)?;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.migrated_pool = ctx.accounts.pool_state.key();
+    pool.migrated_at = Clock::get()?.unix_timestamp;
+
+    let event = MigrationEvent {
         mint_address: ctx.accounts.mint_address.key(),
         raydium_pool: ctx.accounts.pool_state.key(),
         contract: ctx.accounts.cp_swap_program.key(),
-    });
+    };
+    #[cfg(feature = "log-events")]
+    emit!(event);
+    #[cfg(not(feature = "log-events"))]
+    emit_cpi!(event);
 
     Ok(())
 }
+
+impl<'info> ProxyInitialize<'info> {
+    fn withdraw_reserves(&self, base_is_token_0: bool, pool_bump: u8) -> Result<()> {
+        let (base_to, quote_to) = if base_is_token_0 {
+            (&self.creator_token_0, &self.creator_token_1)
+        } else {
+            (&self.creator_token_1, &self.creator_token_0)
+        };
+        let (base_mint, base_program, quote_mint, quote_program) = if base_is_token_0 {
+            (&self.token_0_mint, &self.token_0_program, &self.token_1_mint, &self.token_1_program)
+        } else {
+            (&self.token_1_mint, &self.token_1_program, &self.token_0_mint, &self.token_0_program)
+        };
+
+        self.transfer_from_pool(&self.base_token_pool, base_to, base_mint, base_program, pool_bump)?;
+        self.transfer_from_pool(&self.quote_token_pool, quote_to, quote_mint, quote_program, pool_bump)
+    }
+
+    fn transfer_from_pool(
+        &self,
+        from: &InterfaceAccount<'info, TokenInterAccount>,
+        to: &InterfaceAccount<'info, TokenInterAccount>,
+        mint: &InterfaceAccount<'info, InterfaceMint>,
+        token_program: &Interface<'info, TokenInterface>,
+        pool_bump: u8
+    ) -> Result<()> {
+        let mint_bytes = self.mint_address.key().to_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[&mint_bytes, POOL_SEED.as_bytes(), &[pool_bump]]];
+        transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: from.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: self.pool.to_account_info(),
+                },
+                signer_seeds
+            ),
+            from.amount,
+            mint.decimals
+        )
+    }
+
+    fn initialize_raydium_pool(
+        &self,
+        init_amount_0: u64,
+        init_amount_1: u64,
+        liquidity_owner_bump: u8
+    ) -> Result<()> {
+        let mint_bytes = self.mint_address.key().to_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[
+            &[&mint_bytes, LIQUIDITY_OWNER_SEED.as_bytes(), &[liquidity_owner_bump]],
+        ];
+        cpi::initialize(
+            CpiContext::new_with_signer(
+                self.cp_swap_program.to_account_info(),
+                cpi::accounts::Initialize {
+                    creator: self.liquidity_owner.to_account_info(),
+                    amm_config: self.amm_config.to_account_info(),
+                    authority: self.authority.to_account_info(),
+                    pool_state: self.pool_state.to_account_info(),
+                    token_0_mint: self.token_0_mint.to_account_info(),
+                    token_1_mint: self.token_1_mint.to_account_info(),
+                    lp_mint: self.lp_mint.to_account_info(),
+                    creator_token_0: self.creator_token_0.to_account_info(),
+                    creator_token_1: self.creator_token_1.to_account_info(),
+                    creator_lp_token: self.creator_lp_token.to_account_info(),
+                    token_0_vault: self.token_0_vault.to_account_info(),
+                    token_1_vault: self.token_1_vault.to_account_info(),
+                    create_pool_fee: self.create_pool_fee.to_account_info(),
+                    observation_state: self.observation_state.to_account_info(),
+                    token_program: self.token_program.to_account_info(),
+                    token_0_program: self.token_0_program.to_account_info(),
+                    token_1_program: self.token_1_program.to_account_info(),
+                    associated_token_program: self.associated_token_program.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                    rent: self.rent.to_account_info(),
+                },
+                signer_seeds
+            ),
+            init_amount_0,
+            init_amount_1,
+            0
+        )
+    }
+
+    // Metadata stays editable by the creator while trading on the curve, and is frozen for good
+    // once liquidity moves to Raydium
+    fn lock_token_metadata(&self, pool_bump: u8) -> Result<()> {
+        let mint_bytes = self.mint_address.key().to_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[&mint_bytes, POOL_SEED.as_bytes(), &[pool_bump]]];
+
+        if *self.mint_address.to_account_info().owner == spl_token_2022::ID {
+            return token_metadata_update_authority(
+                CpiContext::new_with_signer(
+                    self.quote_token_program.to_account_info(),
+                    TokenMetadataUpdateAuthority {
+                        program_id: self.quote_token_program.to_account_info(),
+                        metadata: self.mint_address.to_account_info(),
+                        current_authority: self.pool.to_account_info(),
+                        new_authority: self.pool.to_account_info(),
+                    },
+                    signer_seeds
+                ),
+                OptionalNonZeroPubkey::default()
+            );
+        }
+
+        let metadata = self.metadata.as_ref().ok_or(CustomError::MissingMetadataAccounts)?;
+        let token_metadata_program = self.token_metadata_program
+            .as_ref()
+            .ok_or(CustomError::MissingMetadataAccounts)?;
+
+        update_metadata_accounts_v2(
+            CpiContext::new_with_signer(
+                token_metadata_program.to_account_info(),
+                UpdateMetadataAccountsV2 {
+                    metadata: metadata.to_account_info(),
+                    update_authority: self.pool.to_account_info(),
+                },
+                signer_seeds
+            ),
+            None,
+            None,
+            None,
+            Some(false)
+        )
+    }
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint as InterfaceMint;
+
+use crate::consts::*;
+use crate::errors::CustomError;
+use crate::state::{ BaseTokenConfiguration, BondingCurve, Pool, SwapQuote };
+use crate::utils::get_transfer_fee;
+
+#[derive(Accounts)]
+pub struct Quote<'info> {
+    #[account(
+        seeds = [BASE_TOKEN_CONFIG_SEED.as_bytes(), &base_token_configuration.base_token_mint.to_bytes()],
+        bump
+    )]
+    pub base_token_configuration: Account<'info, BaseTokenConfiguration>,
+
+    #[account(
+      seeds = [&mint_address.key().to_bytes(), CURVE_SEED.as_bytes()],
+      bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    // Ties the configuration to the base token the curve trades against
+    #[account(
+      seeds = [&mint_address.key().to_bytes(), POOL_SEED.as_bytes()],
+      bump,
+      constraint = pool.base_token_mint == base_token_configuration.base_token_mint @ CustomError::InvalidBaseToken
+    )]
+    pub pool: Account<'info, Pool>,
+
+    /// CHECK: Only used to derive the bonding curve and pool
+    pub mint_address: UncheckedAccount<'info>,
+
+    // Read for its Token-2022 transfer fee
+    #[account(address = base_token_configuration.base_token_mint)]
+    pub base_token_mint: InterfaceAccount<'info, InterfaceMint>,
+}
+
+pub fn quote_buy_handler(ctx: Context<Quote>, base_input_amount: u64) -> Result<SwapQuote> {
+    ctx.accounts.bonding_curve.quote_buy(&ctx.accounts.base_token_configuration, base_input_amount, |amount|
+        get_transfer_fee(&ctx.accounts.base_token_mint, amount)
+    )
+}
+
+pub fn quote_sell_handler(ctx: Context<Quote>, quote_input_amount: u64) -> Result<SwapQuote> {
+    ctx.accounts.bonding_curve.quote_sell(&ctx.accounts.base_token_configuration, quote_input_amount, |amount|
+        get_transfer_fee(&ctx.accounts.base_token_mint, amount)
+    )
+}
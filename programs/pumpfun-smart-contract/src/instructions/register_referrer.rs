@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::consts::*;
+use crate::errors::CustomError;
+use crate::state::{ InitializeConfiguration, Referrer };
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(seeds = [CONFIG_SEED.as_bytes()], bump)]
+    pub global_configuration: Account<'info, InitializeConfiguration>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [REFERRER_SEED.as_bytes(), &wallet.key().to_bytes()],
+        space = 8 + Referrer::SIZE,
+        bump
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    ///CHECK: Wallet receiving the referral fees
+    pub wallet: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = admin.key() == global_configuration.admin.key() @ CustomError::InvalidAdminAccount
+    )]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RegisterReferrer>) -> Result<()> {
+    let referrer = &mut ctx.accounts.referrer;
+
+    referrer.wallet = ctx.accounts.wallet.key();
+    referrer.total_earned = 0;
+    referrer.referred_trades = 0;
+
+    Ok(())
+}
@@ -1,16 +1,40 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{ self, transfer_checked, Mint, Token, TokenAccount, TransferChecked },
+    token_interface::{
+        transfer_checked,
+        Mint as InterfaceMint,
+        TokenAccount as TokenInterAccount,
+        TokenInterface,
+        TransferChecked,
+    },
 };
 
 use crate::{
-    consts::{ CONFIG_SEED, CURVE_SEED, POOL_SEED },
+    consts::{
+        CONFIG_SEED,
+        BASE_TOKEN_CONFIG_SEED,
+        CURVE_SEED,
+        POOL_SEED,
+        OBSERVATION_SEED,
+        CREATOR_VAULT_SEED,
+        REFERRER_SEED,
+        PROTOCOL_FEE_VAULT_SEED,
+    },
     errors::CustomError,
-    state::{ BondingCurve, InitializeConfiguration },
-    events::TransactionEvent,
+    state::{
+        BondingCurve,
+        BaseTokenConfiguration,
+        InitializeConfiguration,
+        ObservationState,
+        Pool,
+        Referrer,
+    },
+    events::{ TradeOperation, TransactionEvent },
+    utils::{ get_transfer_fee, is_native_mint, unwrap_sol },
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Sell<'info> {
     #[account(
@@ -20,40 +44,55 @@ pub struct Sell<'info> {
     )]
     pub global_configuration: Account<'info, InitializeConfiguration>,
 
+    #[account(
+        seeds = [BASE_TOKEN_CONFIG_SEED.as_bytes(), &base_token_mint.key().to_bytes()],
+        bump
+    )]
+    pub base_token_configuration: Account<'info, BaseTokenConfiguration>,
+
     #[account(
       mut, 
       seeds = [&mint_address.key().to_bytes(), CURVE_SEED.as_bytes()],
-      bump
+      bump,
+      constraint = !bonding_curve.is_completed @ CustomError::BondingCurveIsCompleted
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
 
-    pub mint_address: Account<'info, Mint>,
+    #[account(
+      mut,
+      seeds = [&mint_address.key().to_bytes(), OBSERVATION_SEED.as_bytes()],
+      bump
+    )]
+    pub price_observation: AccountLoader<'info, ObservationState>,
+
+    pub mint_address: InterfaceAccount<'info, InterfaceMint>,
 
-    pub base_token_mint: Account<'info, Mint>,
+    pub base_token_mint: InterfaceAccount<'info, InterfaceMint>,
 
     #[account(
-      mut, 
+      init_if_needed,
+      payer = payer,
       associated_token::mint = base_token_mint,
       associated_token::authority = payer,
       associated_token::token_program = token_program,
     )]
-    pub user_base_token_ata: Account<'info, TokenAccount>,
+    pub user_base_token_ata: InterfaceAccount<'info, TokenInterAccount>,
 
     #[account(
       mut,
       associated_token::mint = mint_address,
       associated_token::authority = payer,
-      associated_token::token_program = token_program
+      associated_token::token_program = quote_token_program
     )]
-    pub user_quote_token_ata: Account<'info, TokenAccount>,
+    pub user_quote_token_ata: InterfaceAccount<'info, TokenInterAccount>,
 
-    ///CHECK:
     #[account(
       mut,
       seeds = [&mint_address.key().to_bytes(), POOL_SEED.as_bytes()],
-      bump
+      bump,
+      constraint = pool.base_token_mint == base_token_mint.key() @ CustomError::InvalidBaseToken
     )]
-    pub pool: AccountInfo<'info>,
+    pub pool: Account<'info, Pool>,
 
     ///CHECK:
     #[account(
@@ -62,69 +101,189 @@ pub struct Sell<'info> {
         associated_token::authority = pool,
         associated_token::token_program = token_program
     )]
-    pub base_token_pool: Account<'info, TokenAccount>,
+    pub base_token_pool: InterfaceAccount<'info, TokenInterAccount>,
 
     ///CHECK:
     #[account(
       mut, 
       associated_token::mint = mint_address,
       associated_token::authority = pool,
-      associated_token::token_program = token_program
+      associated_token::token_program = quote_token_program
     )]
-    pub quote_token_pool: Account<'info, TokenAccount>,
+    pub quote_token_pool: InterfaceAccount<'info, TokenInterAccount>,
 
     ///CHECK:
-    #[account(mut)]
-    pub fee_account: AccountInfo<'info>,
+    #[account(
+      seeds = [PROTOCOL_FEE_VAULT_SEED.as_bytes(), &base_token_mint.key().to_bytes()],
+      bump
+    )]
+    pub protocol_fee_vault: AccountInfo<'info>,
 
     #[account(
         mut,
         associated_token::mint = base_token_mint,
-        associated_token::authority = fee_account,
+        associated_token::authority = protocol_fee_vault,
         associated_token::token_program = token_program
     )]
-    pub fee_base_token_ata: Account<'info, TokenAccount>,
+    pub protocol_fee_vault_ata: InterfaceAccount<'info, TokenInterAccount>,
+
+    ///CHECK:
+    #[account(
+      seeds = [&mint_address.key().to_bytes(), CREATOR_VAULT_SEED.as_bytes()],
+      bump
+    )]
+    pub creator_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = creator_vault,
+        associated_token::token_program = token_program
+    )]
+    pub creator_vault_base_token_ata: InterfaceAccount<'info, TokenInterAccount>,
+
+    #[account(mut, seeds = [REFERRER_SEED.as_bytes(), &referrer.wallet.to_bytes()], bump)]
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    #[account(mut)]
+    pub referrer_base_token_ata: Option<InterfaceAccount<'info, TokenInterAccount>>,
 
     #[account(mut)]
     pub payer: Signer<'info>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<Sell>, quote_input_amount: u64, expected_amount: u64) -> Result<()> {
-    let bonding_curve = &mut ctx.accounts.bonding_curve;
-    let k_value = bonding_curve.k_value;
+    let pool_bump = ctx.bumps.pool;
+    let clock = Clock::get()?;
+    ctx.accounts.price_observation
+        .load_mut()?
+        .update(clock.unix_timestamp as u64, ctx.accounts.bonding_curve.price_x32()?);
 
-    let denominator = (bonding_curve.init_virtual_base_token +
-        bonding_curve.base_token_reserves) as u128;
-    let numerator = (bonding_curve.quote_token_reserves + quote_input_amount) as u128;
-    let div_result = k_value
-        .checked_div(numerator)
-        .ok_or_else(|| error!(CustomError::OverflowEstimateOutBase))?;
+    let swap = ctx.accounts.bonding_curve.swap_sell(
+        &ctx.accounts.base_token_configuration,
+        quote_input_amount,
+        |amount| get_transfer_fee(&ctx.accounts.base_token_mint, amount)
+    )?;
+    require!(swap.amount_out >= expected_amount, CustomError::SlippageExceeded);
 
-    // Prevent subtraction overflow
-    let estimated_out_base = denominator
-        .checked_sub(div_result)
-        .ok_or_else(|| error!(CustomError::OverflowEstimateOutBase))? as u64;
+    let referral_fee_share = match ctx.accounts.referrer {
+        Some(_) => ctx.accounts.global_configuration.referral_fee_share,
+        None => 0,
+    };
+    let fees = ctx.accounts.bonding_curve.split_platform_fee(swap.platform_fee, referral_fee_share)?;
 
-    // Apply platform fee
+    let unwrap =
+        is_native_mint(&ctx.accounts.base_token_mint.key()) && ctx.accounts.user_base_token_ata.amount == 0;
 
-    // Transfer quote tokens from user to pool
+    // Transfer quote tokens from user to pool and base tokens from pool to user
+    ctx.accounts.transfer_to_pool(quote_input_amount)?;
+    ctx.accounts.transfer_from_pool(&ctx.accounts.user_base_token_ata, swap.pool_amount_out, pool_bump)?;
 
-    emit!(TransactionEvent {
-        operation: "Sell".to_string(),
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.base_token_reserves = swap.after.base_token_reserves;
+    bonding_curve.quote_token_reserves = swap.after.quote_token_reserves;
+    let sequence = ctx.accounts.pool.record_trade(false, swap.gross_amount_out, swap.platform_fee)?;
+
+    ctx.accounts.quote_token_pool.reload()?;
+    let reserves = bonding_curve.reserves(ctx.accounts.quote_token_pool.amount)?;
+    let event = TransactionEvent {
+        operation: TradeOperation::Sell,
         creator: ctx.accounts.payer.key(),
         input_amount: quote_input_amount,
-        output_amount: estimated_out_base,
-        platform_fee: platform_fee,
+        output_amount: swap.amount_out,
+        platform_fee: swap.platform_fee,
         base_token_mint: ctx.accounts.base_token_mint.key(),
         mint_address: ctx.accounts.mint_address.key(),
-        fee_wallet: ctx.accounts.fee_account.key(),
-    });
+        fee_wallet: ctx.accounts.protocol_fee_vault.key(),
+        referrer: ctx.accounts.referrer.as_ref().map(|referrer| referrer.wallet),
+        virtual_base_reserves: reserves.virtual_base,
+        virtual_quote_reserves: reserves.virtual_quote,
+        real_base_reserves: reserves.real_base,
+        real_quote_reserves: reserves.real_quote,
+        price_x32: bonding_curve.price_x32()?,
+        timestamp: clock.unix_timestamp,
+        slot: clock.slot,
+        sequence,
+    };
+    #[cfg(feature = "log-events")]
+    emit!(event);
+    #[cfg(not(feature = "log-events"))]
+    emit_cpi!(event);
+
+    ctx.accounts.transfer_from_pool(&ctx.accounts.protocol_fee_vault_ata, fees.protocol_fee, pool_bump)?;
+    ctx.accounts.transfer_from_pool(
+        &ctx.accounts.creator_vault_base_token_ata,
+        fees.creator_fee,
+        pool_bump
+    )?;
 
-    bonding_curve.base_token_reserves -= estimated_out_base;
-    bonding_curve.quote_token_reserves += quote_input_amount;
+    if let Some(referrer) = &ctx.accounts.referrer {
+        require!(referrer.wallet != ctx.accounts.payer.key(), CustomError::SelfReferral);
+        let referrer_base_token_ata = ctx.accounts.referrer_base_token_ata
+            .as_ref()
+            .ok_or(CustomError::InvalidReferrer)?;
+        referrer.validate_payout_account(
+            referrer_base_token_ata,
+            ctx.accounts.base_token_mint.key()
+        )?;
+        ctx.accounts.transfer_from_pool(referrer_base_token_ata, fees.referral_fee, pool_bump)?;
+    }
+    if let Some(referrer) = &mut ctx.accounts.referrer {
+        referrer.record(fees.referral_fee)?;
+    }
+
+    if unwrap {
+        unwrap_sol(&ctx.accounts.payer, &ctx.accounts.user_base_token_ata, &ctx.accounts.token_program)?;
+    }
     Ok(())
 }
+
+impl<'info> Sell<'info> {
+    fn transfer_to_pool(&self, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        transfer_checked(
+            CpiContext::new(self.quote_token_program.to_account_info(), TransferChecked {
+                from: self.user_quote_token_ata.to_account_info(),
+                mint: self.mint_address.to_account_info(),
+                to: self.quote_token_pool.to_account_info(),
+                authority: self.payer.to_account_info(),
+            }),
+            amount,
+            self.mint_address.decimals
+        )
+    }
+
+    fn transfer_from_pool(
+        &self,
+        to: &InterfaceAccount<'info, TokenInterAccount>,
+        amount: u64,
+        pool_bump: u8
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let mint_bytes = self.mint_address.key().to_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[&mint_bytes, POOL_SEED.as_bytes(), &[pool_bump]]];
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.base_token_pool.to_account_info(),
+                    mint: self.base_token_mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: self.pool.to_account_info(),
+                },
+                signer_seeds
+            ),
+            amount,
+            self.base_token_mint.decimals
+        )
+    }
+}
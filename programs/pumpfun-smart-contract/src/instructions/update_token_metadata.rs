@@ -0,0 +1,182 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{
+        mpl_token_metadata::types::DataV2,
+        update_metadata_accounts_v2,
+        Metadata,
+        UpdateMetadataAccountsV2,
+    },
+    token_2022::spl_token_2022::{
+        self,
+        extension::{ BaseStateWithExtensions, StateWithExtensions },
+        state::Mint as Token2022Mint,
+    },
+    token_2022_extensions::spl_token_metadata_interface::state::{ Field, TokenMetadata },
+    token_interface::{
+        token_metadata_update_field,
+        Mint as InterfaceMint,
+        TokenInterface,
+        TokenMetadataUpdateField,
+    },
+};
+
+use crate::consts::*;
+use crate::errors::CustomError;
+use crate::events::TokenMetadataUpdated;
+use crate::state::{ BondingCurve, Pool };
+use crate::utils::validate_token_metadata;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateTokenMetadata<'info> {
+    #[account(
+      seeds = [&mint_address.key().to_bytes(), CURVE_SEED.as_bytes()],
+      bump,
+      constraint = !bonding_curve.is_completed @ CustomError::BondingCurveIsCompleted
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(mut)]
+    pub mint_address: InterfaceAccount<'info, InterfaceMint>,
+
+    /// Update authority of the token metadata
+    #[account(
+      seeds = [&mint_address.key().to_bytes(), POOL_SEED.as_bytes()],
+      bump
+    )]
+    pub pool: Account<'info, Pool>,
+
+    /// CHECK: Metaplex metadata account, only for spl-token mints. Its owner and update
+    /// authority are checked by the metadata program
+    #[account(mut)]
+    pub metadata: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        constraint = creator.key() == pool.creator @ CustomError::InvalidCreator
+    )]
+    pub creator: Signer<'info>,
+
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateTokenMetadata>,
+    name: String,
+    symbol: String,
+    uri: String
+) -> Result<()> {
+    validate_token_metadata(&name, &symbol, &uri)?;
+
+    let mint_bytes = ctx.accounts.mint_address.key().to_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[&mint_bytes, POOL_SEED.as_bytes(), &[ctx.bumps.pool]]];
+
+    if *ctx.accounts.mint_address.to_account_info().owner == spl_token_2022::ID {
+        ctx.accounts.fund_token_2022_metadata(&name, &symbol, &uri)?;
+
+        for (field, value) in [
+            (Field::Name, name.clone()),
+            (Field::Symbol, symbol.clone()),
+            (Field::Uri, uri.clone()),
+        ] {
+            token_metadata_update_field(
+                CpiContext::new_with_signer(
+                    ctx.accounts.quote_token_program.to_account_info(),
+                    TokenMetadataUpdateField {
+                        program_id: ctx.accounts.quote_token_program.to_account_info(),
+                        metadata: ctx.accounts.mint_address.to_account_info(),
+                        update_authority: ctx.accounts.pool.to_account_info(),
+                    },
+                    signer_seeds
+                ),
+                field,
+                value
+            )?;
+        }
+    } else {
+        let metadata = ctx.accounts.metadata.as_ref().ok_or(CustomError::MissingMetadataAccounts)?;
+        let token_metadata_program = ctx.accounts.token_metadata_program
+            .as_ref()
+            .ok_or(CustomError::MissingMetadataAccounts)?;
+
+        update_metadata_accounts_v2(
+            CpiContext::new_with_signer(
+                token_metadata_program.to_account_info(),
+                UpdateMetadataAccountsV2 {
+                    metadata: metadata.to_account_info(),
+                    update_authority: ctx.accounts.pool.to_account_info(),
+                },
+                signer_seeds
+            ),
+            None,
+            Some(DataV2 {
+                name: name.clone(),
+                symbol: symbol.clone(),
+                uri: uri.clone(),
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            }),
+            None,
+            None
+        )?;
+    }
+
+    let event = TokenMetadataUpdated {
+        mint_address: ctx.accounts.mint_address.key(),
+        creator: ctx.accounts.creator.key(),
+        name,
+        symbol,
+        uri,
+    };
+    #[cfg(feature = "log-events")]
+    emit!(event);
+    #[cfg(not(feature = "log-events"))]
+    emit_cpi!(event);
+
+    Ok(())
+}
+
+impl<'info> UpdateTokenMetadata<'info> {
+    // Token-2022 reallocates the mint in place, so top it up to the new rent-exempt minimum first
+    fn fund_token_2022_metadata(&self, name: &str, symbol: &str, uri: &str) -> Result<()> {
+        let mint_info = self.mint_address.to_account_info();
+        let new_len = {
+            let mint_data = mint_info.try_borrow_data()?;
+            let mint = StateWithExtensions::<Token2022Mint>::unpack(&mint_data)?;
+            let current = mint.get_variable_len_extension::<TokenMetadata>()?;
+            let current_size = current.tlv_size_of()?;
+            let updated = TokenMetadata {
+                name: name.to_string(),
+                symbol: symbol.to_string(),
+                uri: uri.to_string(),
+                ..current
+            };
+            let updated_size = updated.tlv_size_of()?;
+            mint_data
+                .len()
+                .checked_sub(current_size)
+                .and_then(|len| len.checked_add(updated_size))
+                .ok_or(CustomError::MathOverflow)?
+        };
+
+        let shortfall = Rent::get()?.minimum_balance(new_len).saturating_sub(mint_info.lamports());
+        if shortfall == 0 {
+            return Ok(());
+        }
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: self.creator.to_account_info(),
+                    to: mint_info,
+                }
+            ),
+            shortfall
+        )
+    }
+}
@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        transfer_checked,
+        Mint as InterfaceMint,
+        TokenAccount as TokenInterAccount,
+        TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::consts::*;
+use crate::errors::CustomError;
+use crate::events::ProtocolFeesWithdrawn;
+use crate::state::InitializeConfiguration;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawProtocolFees<'info> {
+    #[account(seeds = [CONFIG_SEED.as_bytes()], bump)]
+    pub global_configuration: Account<'info, InitializeConfiguration>,
+
+    pub base_token_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    ///CHECK:
+    #[account(
+      seeds = [PROTOCOL_FEE_VAULT_SEED.as_bytes(), &base_token_mint.key().to_bytes()],
+      bump
+    )]
+    pub protocol_fee_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_token_mint,
+        associated_token::authority = protocol_fee_vault,
+        associated_token::token_program = token_program
+    )]
+    pub protocol_fee_vault_ata: InterfaceAccount<'info, TokenInterAccount>,
+
+    #[account(constraint = admin.key() == global_configuration.admin.key() @ CustomError::InvalidAdminAccount)]
+    pub admin: Signer<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// remaining_accounts: one base token account per configured recipient with a non-zero weight,
+// in the order they appear in `fee_recipients`
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, WithdrawProtocolFees<'info>>) -> Result<()> {
+    let config = &ctx.accounts.global_configuration;
+    let total_weight = config.total_fee_recipient_weight()?;
+    require!(total_weight > 0, CustomError::InvalidFeeRecipients);
+
+    let balance = ctx.accounts.protocol_fee_vault_ata.amount;
+    require!(balance > 0, CustomError::NothingToClaim);
+
+    let recipients: Vec<_> = config.fee_recipients
+        .iter()
+        .filter(|recipient| recipient.weight > 0)
+        .collect();
+    require!(
+        ctx.remaining_accounts.len() == recipients.len(),
+        CustomError::InvalidFeeRecipients
+    );
+
+    let base_token_mint = ctx.accounts.base_token_mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[
+        &[
+            PROTOCOL_FEE_VAULT_SEED.as_bytes(),
+            &base_token_mint.to_bytes(),
+            &[ctx.bumps.protocol_fee_vault],
+        ],
+    ];
+
+    let mut distributed: u64 = 0;
+    for (index, (recipient, destination)) in recipients
+        .iter()
+        .zip(ctx.remaining_accounts.iter())
+        .enumerate() {
+        let destination_ata = InterfaceAccount::<TokenInterAccount>::try_from(destination)?;
+        require!(
+            destination_ata.owner == recipient.wallet && destination_ata.mint == base_token_mint,
+            CustomError::InvalidFeeRecipients
+        );
+
+        // The last recipient takes the rounding dust
+        let amount = if index == recipients.len() - 1 {
+            balance.checked_sub(distributed).ok_or(CustomError::MathUnderflow)?
+        } else {
+            let share = (balance as u128)
+                .checked_mul(recipient.weight as u128)
+                .ok_or(CustomError::MathOverflow)?
+                .checked_div(total_weight as u128)
+                .ok_or(CustomError::MathDivisionByZero)?;
+            u64::try_from(share).map_err(|_| CustomError::MathOverflow)?
+        };
+        distributed = distributed.checked_add(amount).ok_or(CustomError::MathOverflow)?;
+
+        if amount == 0 {
+            continue;
+        }
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.protocol_fee_vault_ata.to_account_info(),
+                    mint: ctx.accounts.base_token_mint.to_account_info(),
+                    to: destination.clone(),
+                    authority: ctx.accounts.protocol_fee_vault.to_account_info(),
+                },
+                signer_seeds
+            ),
+            amount,
+            ctx.accounts.base_token_mint.decimals
+        )?;
+    }
+
+    let event = ProtocolFeesWithdrawn {
+        base_token_mint,
+        amount: balance,
+        recipients: recipients
+            .iter()
+            .map(|recipient| recipient.wallet)
+            .collect(),
+    };
+    #[cfg(feature = "log-events")]
+    emit!(event);
+    #[cfg(not(feature = "log-events"))]
+    emit_cpi!(event);
+
+    Ok(())
+}
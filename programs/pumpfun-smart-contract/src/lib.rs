@@ -5,6 +5,7 @@ pub mod consts;
 pub mod events;
 pub mod errors;
 pub mod instructions;
+pub mod utils;
 
 use instructions::*;
 use crate::state::*;
@@ -17,25 +18,29 @@ pub mod pumpfun_smart_contract {
 
     pub fn initialize(
         ctx: Context<Initialize>,
+        fee_wallet: Pubkey,
+        raydium_migration_fee: u64
+    ) -> Result<()> {
+        initialize::handler(ctx, fee_wallet, raydium_migration_fee)?;
+
+        Ok(())
+    }
+
+    pub fn add_base_token(
+        ctx: Context<AddBaseToken>,
         swap_fee: u64,
         bonding_curve_limitation: u64,
         initial_virtual_base_token: u64,
         initial_virtual_quote_token: u64,
-        create_pool_fee_amount: u64,
-        base_token_ca: Pubkey,
-        fee_wallet: Pubkey,
-        raydium_migration_fee: u64
+        create_pool_fee_amount: u64
     ) -> Result<()> {
-        initialize::handler(
+        add_base_token::handler(
             ctx,
             swap_fee,
             bonding_curve_limitation,
             initial_virtual_base_token,
             initial_virtual_quote_token,
-            create_pool_fee_amount,
-            base_token_ca,
-            fee_wallet,
-            raydium_migration_fee
+            create_pool_fee_amount
         )?;
 
         Ok(())
@@ -47,7 +52,10 @@ pub mod pumpfun_smart_contract {
         symbol: String,
         uri: String,
         dev_buy_amount: u64,
-        initial_token_transfer_percent: u64
+        initial_token_transfer_percent: u64,
+        presale: Option<PresaleConfig>,
+        use_token_2022: bool,
+        mint_nonce: Option<u64>
     ) -> Result<()> {
         create_pool::handler(
             ctx,
@@ -55,7 +63,10 @@ pub mod pumpfun_smart_contract {
             symbol,
             uri,
             dev_buy_amount,
-            initial_token_transfer_percent
+            initial_token_transfer_percent,
+            presale,
+            use_token_2022,
+            mint_nonce
         )?;
         Ok(())
     }
@@ -65,6 +76,17 @@ pub mod pumpfun_smart_contract {
         Ok(())
     }
 
+    pub fn presale_buy(
+        ctx: Context<PresaleBuy>,
+        base_input_amount: u64,
+        expected_amount: u64,
+        allocation: u64,
+        proof: Vec<[u8; 32]>
+    ) -> Result<()> {
+        presale_buy::handler(ctx, base_input_amount, expected_amount, allocation, proof)?;
+        Ok(())
+    }
+
     pub fn sell(ctx: Context<Sell>, quote_input_amount: u64, expected_amount: u64) -> Result<()> {
         sell::handler(ctx, quote_input_amount, expected_amount)?;
         Ok(())
@@ -75,16 +97,103 @@ pub mod pumpfun_smart_contract {
         Ok(())
     }
 
-    pub fn set_swap_fee(ctx: Context<SetGlobalConfiguration>, new_swap_fee: u64) -> Result<()> {
-        ctx.accounts.global_configuration.swap_fee = new_swap_fee;
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        claim_creator_fees::handler(ctx)?;
+        Ok(())
+    }
+
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        register_referrer::handler(ctx)?;
+        Ok(())
+    }
+
+    pub fn withdraw_protocol_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawProtocolFees<'info>>
+    ) -> Result<()> {
+        withdraw_protocol_fees::handler(ctx)?;
+        Ok(())
+    }
+
+    pub fn update_token_metadata(
+        ctx: Context<UpdateTokenMetadata>,
+        name: String,
+        symbol: String,
+        uri: String
+    ) -> Result<()> {
+        update_token_metadata::handler(ctx, name, symbol, uri)?;
+        Ok(())
+    }
+
+    pub fn get_twap(ctx: Context<GetTwap>, window: u64) -> Result<u128> {
+        get_twap::handler(ctx, window)
+    }
+
+    // Both quotes are returned through `set_return_data` for simulations and CPI callers
+    pub fn quote_buy(ctx: Context<Quote>, base_input_amount: u64) -> Result<SwapQuote> {
+        quote::quote_buy_handler(ctx, base_input_amount)
+    }
+
+    pub fn quote_sell(ctx: Context<Quote>, quote_input_amount: u64) -> Result<SwapQuote> {
+        quote::quote_sell_handler(ctx, quote_input_amount)
+    }
+
+    pub fn set_swap_fee(ctx: Context<SetBaseTokenConfiguration>, new_swap_fee: u64) -> Result<()> {
+        require!(new_swap_fee <= 10000, errors::CustomError::InvalidSwapFee);
+        ctx.accounts.base_token_configuration.swap_fee = new_swap_fee;
         Ok(())
     }
 
     pub fn set_bonding_curve_limitaion(
-        ctx: Context<SetGlobalConfiguration>,
+        ctx: Context<SetBaseTokenConfiguration>,
         new_bonding_curve_limitaion: u64
     ) -> Result<()> {
-        ctx.accounts.global_configuration.bonding_curve_limitation = new_bonding_curve_limitaion;
+        ctx.accounts.base_token_configuration.bonding_curve_limitation = new_bonding_curve_limitaion;
+        Ok(())
+    }
+
+    pub fn set_creator_fee_share(
+        ctx: Context<SetGlobalConfiguration>,
+        new_creator_fee_share: u64
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.global_configuration;
+        let total_fee_share = new_creator_fee_share
+            .checked_add(config.referral_fee_share)
+            .ok_or(errors::CustomError::MathOverflow)?;
+        require!(total_fee_share <= 10000, errors::CustomError::InvalidFeeShare);
+        config.creator_fee_share = new_creator_fee_share;
+        Ok(())
+    }
+
+    pub fn set_referral_fee_share(
+        ctx: Context<SetGlobalConfiguration>,
+        new_referral_fee_share: u64
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.global_configuration;
+        let total_fee_share = config.creator_fee_share
+            .checked_add(new_referral_fee_share)
+            .ok_or(errors::CustomError::MathOverflow)?;
+        require!(total_fee_share <= 10000, errors::CustomError::InvalidFeeShare);
+        config.referral_fee_share = new_referral_fee_share;
+        Ok(())
+    }
+
+    pub fn set_fee_recipients(
+        ctx: Context<SetGlobalConfiguration>,
+        new_fee_recipients: Vec<FeeRecipient>
+    ) -> Result<()> {
+        require!(
+            new_fee_recipients.len() <= consts::MAX_FEE_RECIPIENTS &&
+                new_fee_recipients.iter().any(|recipient| recipient.weight > 0),
+            errors::CustomError::InvalidFeeRecipients
+        );
+
+        let config = &mut ctx.accounts.global_configuration;
+        config.fee_recipients = Default::default();
+        for (slot, recipient) in config.fee_recipients.iter_mut().zip(new_fee_recipients) {
+            *slot = recipient;
+        }
+        // Payouts divide by the total weight, so it has to fit
+        config.total_fee_recipient_weight()?;
         Ok(())
     }
 }
@@ -1,25 +1,61 @@
 use anchor_lang::prelude::*;
-use std::ops::{ Div, Mul };
+use anchor_spl::token_interface::TokenAccount as TokenInterAccount;
 
 use crate::consts::*;
 use crate::errors::CustomError;
 
 #[account]
 pub struct InitializeConfiguration {
-    pub swap_fee: u64,
-    pub bonding_curve_limitation: u64,
-    pub initial_virtual_base_token: u64,
-    pub initial_virtual_quote_token: u64,
-    pub create_pool_fee_amount: u64,
-    pub base_token_ca: Pubkey,
     pub fee_wallet: Pubkey,
     pub migration_authority: Pubkey,
     pub admin: Pubkey,
     pub raydium_migration_fee: u64,
+    pub creator_fee_share: u64, // share of platform_fee paid to the pool creator, in bps
+    pub referral_fee_share: u64, // share of platform_fee paid to the referrer, in bps
+    pub fee_recipients: [FeeRecipient; MAX_FEE_RECIPIENTS], // payout split of the protocol fee vault
 }
 
 impl InitializeConfiguration {
-    pub const SIZE: usize = 8 * 8 + 32 * 4;
+    pub const SIZE: usize = 8 * 3 + 32 * 3 + FeeRecipient::SIZE * MAX_FEE_RECIPIENTS;
+
+    pub fn total_fee_recipient_weight(&self) -> Result<u64> {
+        self.fee_recipients
+            .iter()
+            .try_fold(0u64, |total, recipient| total.checked_add(recipient.weight))
+            .ok_or(error!(CustomError::MathOverflow))
+    }
+}
+
+// One per supported base token, seeded by its mint
+#[account]
+pub struct BaseTokenConfiguration {
+    pub base_token_mint: Pubkey,
+    pub swap_fee: u64,
+    pub bonding_curve_limitation: u64,
+    pub initial_virtual_base_token: u64,
+    pub initial_virtual_quote_token: u64,
+    pub create_pool_fee_amount: u64,
+}
+
+impl BaseTokenConfiguration {
+    pub const SIZE: usize = 32 + 8 * 5;
+
+    pub fn platform_fee(&self, amount: u64) -> Result<u64> {
+        let fee = (amount as u128)
+            .checked_mul(self.swap_fee as u128)
+            .ok_or(CustomError::MathOverflow)? / 10000;
+        Ok(u64::try_from(fee).map_err(|_| CustomError::MathOverflow)?)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct FeeRecipient {
+    pub wallet: Pubkey,
+    pub weight: u64,
+}
+
+impl FeeRecipient {
+    pub const SIZE: usize = 32 + 8;
 }
 
 #[account]
@@ -31,14 +67,399 @@ pub struct BondingCurve {
     pub base_token_reserves: u64,
     pub k_value: u128, // k = x * y
     pub is_completed: bool,
+    pub presale_merkle_root: [u8; 32], // all zeroes when the pool has no presale
+    pub presale_end_time: i64,
+    pub creator_fee_share: u64, // snapshot of the config value at creation, in bps
 }
 
 impl BondingCurve {
-    pub const SIZE: usize = 49;
+    pub const SIZE: usize = 49 + 32 + 8 + 8;
 
     pub fn get(&self) -> &BondingCurve {
         self
     }
+
+    // Quote tokens released for `base_input_amount` entering the pool, keeping k = x * y
+    pub fn get_buy_amount_out(&self, base_input_amount: u64) -> Result<u64> {
+        let denominator = (self.init_virtual_base_token as u128) +
+            (self.base_token_reserves as u128) +
+            (base_input_amount as u128);
+        let maybe_quote = (self.quote_token_reserves as u128).checked_sub(
+            self.k_value.checked_div(denominator).ok_or(CustomError::MathDivisionByZero)?
+        );
+        Ok(maybe_quote.ok_or_else(|| error!(CustomError::OverflowEstimateOutQuote))? as u64)
+    }
+
+    // Base tokens released for `quote_input_amount` entering the pool, before the platform fee
+    pub fn get_sell_amount_out(&self, quote_input_amount: u64) -> Result<u64> {
+        let denominator = (self.init_virtual_base_token as u128) + (self.base_token_reserves as u128);
+        let numerator = (self.quote_token_reserves as u128) + (quote_input_amount as u128);
+        let div_result = self.k_value
+            .checked_div(numerator)
+            .ok_or_else(|| error!(CustomError::OverflowEstimateOutBase))?;
+
+        // Prevent subtraction overflow
+        let amount_out = denominator
+            .checked_sub(div_result)
+            .ok_or_else(|| error!(CustomError::OverflowEstimateOutBase))?;
+        Ok(u64::try_from(amount_out).map_err(|_| CustomError::OverflowEstimateOutBase)?)
+    }
+
+    pub fn is_limitation_reached(&self, bonding_curve_limitation: u64) -> bool {
+        (self.base_token_reserves as u128) + (self.init_virtual_base_token as u128) >=
+            (bonding_curve_limitation as u128)
+    }
+
+    // Runs a buy against the curve without applying it. `base_transfer_fee` returns what the
+    // base mint withholds from a transfer of the given amount
+    pub fn swap_buy(
+        &self,
+        config: &BaseTokenConfiguration,
+        base_input_amount: u64,
+        base_transfer_fee: impl Fn(u64) -> Result<u64>
+    ) -> Result<Swap> {
+        let platform_fee = config.platform_fee(base_input_amount)?;
+        let pool_amount_in = base_input_amount
+            .checked_sub(platform_fee)
+            .ok_or(CustomError::MathUnderflow)?;
+        // The pool only receives what is left after the base mint's transfer fee
+        let received_amount_in = pool_amount_in
+            .checked_sub(base_transfer_fee(pool_amount_in)?)
+            .ok_or(CustomError::MathUnderflow)?;
+        let amount_out = self.get_buy_amount_out(received_amount_in)?;
+
+        let mut after = self.clone();
+        after.base_token_reserves = after.base_token_reserves
+            .checked_add(received_amount_in)
+            .ok_or(CustomError::MathOverflow)?;
+        after.quote_token_reserves = after.quote_token_reserves
+            .checked_sub(amount_out)
+            .ok_or(CustomError::MathUnderflow)?;
+
+        Ok(Swap {
+            platform_fee,
+            pool_amount_in,
+            gross_amount_out: amount_out,
+            pool_amount_out: amount_out,
+            amount_out,
+            after,
+        })
+    }
+
+    // Runs a sell against the curve without applying it, see `swap_buy`
+    pub fn swap_sell(
+        &self,
+        config: &BaseTokenConfiguration,
+        quote_input_amount: u64,
+        base_transfer_fee: impl Fn(u64) -> Result<u64>
+    ) -> Result<Swap> {
+        let gross_amount_out = self.get_sell_amount_out(quote_input_amount)?;
+        let platform_fee = config.platform_fee(gross_amount_out)?;
+        let pool_amount_out = gross_amount_out
+            .checked_sub(platform_fee)
+            .ok_or(CustomError::MathUnderflow)?;
+        let amount_out = pool_amount_out
+            .checked_sub(base_transfer_fee(pool_amount_out)?)
+            .ok_or(CustomError::MathUnderflow)?;
+
+        let mut after = self.clone();
+        after.base_token_reserves = after.base_token_reserves
+            .checked_sub(gross_amount_out)
+            .ok_or(CustomError::MathUnderflow)?;
+        after.quote_token_reserves = after.quote_token_reserves
+            .checked_add(quote_input_amount)
+            .ok_or(CustomError::MathOverflow)?;
+
+        Ok(Swap {
+            platform_fee,
+            pool_amount_in: quote_input_amount,
+            gross_amount_out,
+            pool_amount_out,
+            amount_out,
+            after,
+        })
+    }
+
+    pub fn quote_buy(
+        &self,
+        config: &BaseTokenConfiguration,
+        base_input_amount: u64,
+        base_transfer_fee: impl Fn(u64) -> Result<u64>
+    ) -> Result<SwapQuote> {
+        let swap = self.swap_buy(config, base_input_amount, base_transfer_fee)?;
+        self.build_quote(&swap, config.bonding_curve_limitation)
+    }
+
+    pub fn quote_sell(
+        &self,
+        config: &BaseTokenConfiguration,
+        quote_input_amount: u64,
+        base_transfer_fee: impl Fn(u64) -> Result<u64>
+    ) -> Result<SwapQuote> {
+        let swap = self.swap_sell(config, quote_input_amount, base_transfer_fee)?;
+        self.build_quote(&swap, config.bonding_curve_limitation)
+    }
+
+    fn build_quote(&self, swap: &Swap, bonding_curve_limitation: u64) -> Result<SwapQuote> {
+        let price_x32 = self.price_x32()?;
+        let new_price_x32 = swap.after.price_x32()?;
+        let price_impact_bps = price_x32
+            .abs_diff(new_price_x32)
+            .checked_mul(10000)
+            .ok_or(CustomError::MathOverflow)?
+            .checked_div(price_x32)
+            .ok_or(CustomError::MathDivisionByZero)?;
+
+        Ok(SwapQuote {
+            amount_out: swap.amount_out,
+            platform_fee: swap.platform_fee,
+            new_price_x32,
+            price_impact_bps: u64::try_from(price_impact_bps).map_err(|_| CustomError::MathOverflow)?,
+            completes_curve: !self.is_completed && swap.after.is_limitation_reached(bonding_curve_limitation),
+        })
+    }
+
+    pub fn virtual_base_reserves(&self) -> Result<u64> {
+        self.init_virtual_base_token
+            .checked_add(self.base_token_reserves)
+            .ok_or(error!(CustomError::MathOverflow))
+    }
+
+    // Reserves reported with trades. The curve prices against the virtual amounts, while
+    // `quote_vault_balance` is what the pool vault actually holds
+    pub fn reserves(&self, quote_vault_balance: u64) -> Result<CurveReserves> {
+        Ok(CurveReserves {
+            virtual_base: self.virtual_base_reserves()?,
+            virtual_quote: self.quote_token_reserves,
+            real_base: self.base_token_reserves,
+            real_quote: quote_vault_balance,
+        })
+    }
+
+    // Virtual base token per quote token, as a Q32 fixed point number
+    pub fn price_x32(&self) -> Result<u128> {
+        let virtual_base = (self.init_virtual_base_token as u128) + (self.base_token_reserves as u128);
+        (virtual_base << 32)
+            .checked_div(self.quote_token_reserves as u128)
+            .ok_or(error!(CustomError::MathDivisionByZero))
+    }
+
+    pub fn is_presale_active(&self, now: i64) -> bool {
+        self.presale_merkle_root != [0u8; 32] && now < self.presale_end_time
+    }
+
+    // Splits platform_fee between the protocol, the pool creator and an optional referrer
+    pub fn split_platform_fee(&self, platform_fee: u64, referral_fee_share: u64) -> Result<FeeSplit> {
+        let share_of = |bps: u64| -> Result<u64> {
+            let share = (platform_fee as u128)
+                .checked_mul(bps as u128)
+                .ok_or(CustomError::MathOverflow)? / 10000;
+            Ok(u64::try_from(share).map_err(|_| CustomError::MathOverflow)?)
+        };
+        let creator_fee = share_of(self.creator_fee_share)?;
+        let remaining_fee = platform_fee.checked_sub(creator_fee).ok_or(CustomError::MathUnderflow)?;
+        // The creator share is snapshotted per pool, so cap the referral at what is left
+        let referral_fee = share_of(referral_fee_share)?.min(remaining_fee);
+        let protocol_fee = remaining_fee.checked_sub(referral_fee).ok_or(CustomError::MathUnderflow)?;
+        Ok(FeeSplit { protocol_fee, creator_fee, referral_fee })
+    }
+}
+
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug)]
+pub struct Observation {
+    pub block_timestamp: u64,
+    pub cumulative_price_x32: u128, // sum of price_x32 * seconds, wrapping
+}
+
+// Ring buffer of curve price observations, modelled on Raydium's observation_state
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+pub struct ObservationState {
+    pub initialized: bool,
+    pub observation_index: u16,
+    pub mint_address: Pubkey,
+    pub observations: [Observation; OBSERVATION_NUM],
+    pub padding: [u64; 4],
+}
+
+impl ObservationState {
+    pub const LEN: usize = 8 + 1 + 2 + 32 + (8 + 16) * OBSERVATION_NUM + 8 * 4;
+
+    // Records the price that was in effect since the last observation, so call it before the
+    // reserves change. At most one observation is written per second.
+    pub fn update(&mut self, block_timestamp: u64, price_x32: u128) {
+        let index = self.observation_index as usize;
+        if !self.initialized {
+            self.initialized = true;
+            self.observations[index].block_timestamp = block_timestamp;
+            self.observations[index].cumulative_price_x32 = 0;
+            return;
+        }
+
+        let last = self.observations[index];
+        let delta_time = block_timestamp.saturating_sub(last.block_timestamp);
+        if delta_time == 0 {
+            return;
+        }
+
+        let next_index = (index + 1) % OBSERVATION_NUM;
+        self.observations[next_index].block_timestamp = block_timestamp;
+        self.observations[next_index].cumulative_price_x32 = last.cumulative_price_x32.wrapping_add(
+            price_x32.wrapping_mul(delta_time as u128)
+        );
+        self.observation_index = next_index as u16;
+    }
+
+    // Time weighted average price_x32 over the `window` seconds ending at `now`
+    pub fn twap_x32(&self, now: u64, window: u64, current_price_x32: u128) -> Result<u128> {
+        require!(self.initialized && window > 0, CustomError::InsufficientObservations);
+
+        let index = self.observation_index as usize;
+        let latest = self.observations[index];
+        let latest_timestamp = latest.block_timestamp;
+        let cumulative_now = latest.cumulative_price_x32.wrapping_add(
+            current_price_x32.wrapping_mul(now.saturating_sub(latest_timestamp) as u128)
+        );
+        let target = now.checked_sub(window).ok_or(CustomError::InsufficientObservations)?;
+
+        // Walk back from the newest observation to the first one at or before the target,
+        // remembering the price that applied right after it
+        let mut price_after = current_price_x32;
+        let mut newer = latest;
+        for offset in 0..OBSERVATION_NUM {
+            let observation = self.observations[(index + OBSERVATION_NUM - offset) % OBSERVATION_NUM];
+            let timestamp = observation.block_timestamp;
+            if offset > 0 {
+                if timestamp == 0 {
+                    break;
+                }
+                let elapsed = newer.block_timestamp
+                    .checked_sub(timestamp)
+                    .ok_or(CustomError::MathUnderflow)?;
+                price_after = newer.cumulative_price_x32
+                    .wrapping_sub(observation.cumulative_price_x32)
+                    .checked_div(elapsed as u128)
+                    .ok_or(CustomError::MathDivisionByZero)?;
+            }
+            if timestamp <= target {
+                let cumulative_target = observation.cumulative_price_x32.wrapping_add(
+                    price_after.wrapping_mul((target - timestamp) as u128)
+                );
+                return Ok(cumulative_now.wrapping_sub(cumulative_target) / (window as u128));
+            }
+            newer = observation;
+        }
+
+        err!(CustomError::InsufficientObservations)
+    }
+}
+
+// Lives at the pool PDA, which is also the authority of the pool vaults
+#[account]
+#[derive(Debug)]
+pub struct Pool {
+    pub creator: Pubkey,
+    pub mint_address: Pubkey,
+    pub base_token_mint: Pubkey,
+    pub bump: u8,
+    pub bonding_curve_bump: u8,
+    pub creator_vault_bump: u8,
+    pub created_at: i64,
+    pub created_slot: u64,
+    pub buy_volume: u128, // cumulative, in base token
+    pub sell_volume: u128, // cumulative, in base token
+    pub trade_count: u64,
+    pub total_fees: u128, // platform fees paid, in base token
+    pub migrated_pool: Pubkey, // Raydium pool state, default until migration
+    pub migrated_at: i64,
+}
+
+impl Pool {
+    pub const SIZE: usize = 32 * 4 + 1 * 3 + 8 * 4 + 16 * 3;
+
+    // Returns the trade's sequence number
+    pub fn record_trade(&mut self, is_buy: bool, base_amount: u64, platform_fee: u64) -> Result<u64> {
+        let volume = if is_buy { &mut self.buy_volume } else { &mut self.sell_volume };
+        *volume = volume.checked_add(base_amount as u128).ok_or(CustomError::MathOverflow)?;
+        self.total_fees = self.total_fees
+            .checked_add(platform_fee as u128)
+            .ok_or(CustomError::MathOverflow)?;
+        self.trade_count = self.trade_count.checked_add(1).ok_or(CustomError::MathOverflow)?;
+        Ok(self.trade_count)
+    }
+
+    pub fn is_migrated(&self) -> bool {
+        self.migrated_pool != Pubkey::default()
+    }
+}
+
+// Returned by the quote instructions through return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub amount_out: u64,
+    pub platform_fee: u64,
+    pub new_price_x32: u128,
+    pub price_impact_bps: u64,
+    pub completes_curve: bool,
+}
+
+// A trade run against the curve. Buys take the platform fee from the input and sells from the
+// output; `amount_out` is what reaches the trader once transfer fees are withheld
+pub struct Swap {
+    pub platform_fee: u64,
+    pub pool_amount_in: u64, // sent by the trader to the pool vault
+    pub gross_amount_out: u64, // released by the curve, before the platform fee
+    pub pool_amount_out: u64, // sent from the pool vault to the trader
+    pub amount_out: u64,
+    pub after: BondingCurve,
+}
+
+pub struct FeeSplit {
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub referral_fee: u64,
+}
+
+pub struct CurveReserves {
+    pub virtual_base: u64,
+    pub virtual_quote: u64,
+    pub real_base: u64,
+    pub real_quote: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct PresaleConfig {
+    pub merkle_root: [u8; 32],
+    pub end_time: i64,
+}
+
+#[account]
+pub struct PresaleReceipt {
+    pub user: Pubkey,
+    pub mint_address: Pubkey,
+    pub purchased_amount: u64, // base token spent during the presale
+}
+
+impl PresaleReceipt {
+    pub const SIZE: usize = 32 * 2 + 8;
+}
+
+#[derive(Accounts)]
+pub struct SetBaseTokenConfiguration<'info> {
+    #[account(constraint = admin.key() == global_configuration.admin.key() @ CustomError::InvalidAdminAccount)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED.as_bytes()], bump)]
+    pub global_configuration: Account<'info, InitializeConfiguration>,
+
+    #[account(
+        mut,
+        seeds = [BASE_TOKEN_CONFIG_SEED.as_bytes(), &base_token_configuration.base_token_mint.to_bytes()],
+        bump
+    )]
+    pub base_token_configuration: Account<'info, BaseTokenConfiguration>,
 }
 
 #[derive(Accounts)]
@@ -53,3 +474,36 @@ pub struct SetGlobalConfiguration<'info> {
     )]
     pub global_configuration: Account<'info, InitializeConfiguration>,
 }
+
+#[account]
+pub struct Referrer {
+    pub wallet: Pubkey,
+    pub total_earned: u64, // lifetime referral fees, in base token
+    pub referred_trades: u64,
+}
+
+impl Referrer {
+    pub const SIZE: usize = 32 + 8 * 2;
+
+    pub fn validate_payout_account(
+        &self,
+        ata: &TokenInterAccount,
+        base_token_mint: Pubkey
+    ) -> Result<()> {
+        require!(
+            ata.owner == self.wallet && ata.mint == base_token_mint,
+            CustomError::InvalidReferrer
+        );
+        Ok(())
+    }
+
+    pub fn record(&mut self, referral_fee: u64) -> Result<()> {
+        self.total_earned = self.total_earned
+            .checked_add(referral_fee)
+            .ok_or(CustomError::MathOverflow)?;
+        self.referred_trades = self.referred_trades
+            .checked_add(1)
+            .ok_or(CustomError::MathOverflow)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::{
+    token::{ spl_token::native_mint, Token },
+    token_2022::spl_token_2022::{
+        extension::{ transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions },
+        state::{ Account as Token2022Account, Mint as Token2022Mint },
+    },
+    token_interface::{
+        close_account,
+        sync_native,
+        CloseAccount,
+        Mint as InterfaceMint,
+        SyncNative,
+        TokenAccount as TokenInterAccount,
+        TokenInterface,
+    },
+};
+
+use crate::consts::*;
+use crate::errors::CustomError;
+
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == native_mint::ID
+}
+
+pub fn validate_token_metadata(name: &str, symbol: &str, uri: &str) -> Result<()> {
+    require!(!name.trim().is_empty(), CustomError::EmptyTokenName);
+    require!(name.len() <= MAX_NAME_LENGTH, CustomError::TokenNameTooLong);
+
+    require!(!symbol.is_empty(), CustomError::EmptyTokenSymbol);
+    require!(symbol.len() <= MAX_SYMBOL_LENGTH, CustomError::TokenSymbolTooLong);
+    require!(
+        symbol.chars().all(|c| c.is_ascii_alphanumeric()),
+        CustomError::InvalidTokenSymbol
+    );
+
+    require!(uri.len() <= MAX_URI_LENGTH, CustomError::TokenUriTooLong);
+    require!(
+        ALLOWED_URI_SCHEMES.iter().any(|scheme| uri.starts_with(scheme)),
+        CustomError::InvalidTokenUriScheme
+    );
+
+    Ok(())
+}
+
+// Amount withheld by a Token-2022 transfer-fee extension when `amount` is sent
+pub fn get_transfer_fee(mint: &InterfaceAccount<InterfaceMint>, amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == Token::id() {
+        return Ok(0);
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Token2022Mint>::unpack(&mint_data)?;
+    let fee = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) =>
+            transfer_fee_config
+                .calculate_epoch_fee(Clock::get()?.epoch, amount)
+                .ok_or(CustomError::MathOverflow)?,
+        Err(_) => 0,
+    };
+    Ok(fee)
+}
+
+// Balance of a token account that is not deserialized by Anchor, for either token program
+pub fn token_account_amount(account: &AccountInfo) -> Result<u64> {
+    let data = account.try_borrow_data()?;
+    Ok(StateWithExtensions::<Token2022Account>::unpack(&data)?.base.amount)
+}
+
+// Tops up a wSOL account with lamports from the owner so it holds at least `amount`
+pub fn wrap_sol<'info>(
+    owner: &Signer<'info>,
+    wsol_account: &InterfaceAccount<'info, TokenInterAccount>,
+    system_program: &Program<'info, System>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64
+) -> Result<()> {
+    let shortfall = amount.saturating_sub(wsol_account.amount);
+    if shortfall == 0 {
+        return Ok(());
+    }
+
+    system_program::transfer(
+        CpiContext::new(system_program.to_account_info(), system_program::Transfer {
+            from: owner.to_account_info(),
+            to: wsol_account.to_account_info(),
+        }),
+        shortfall
+    )?;
+    sync_native(
+        CpiContext::new(token_program.to_account_info(), SyncNative {
+            account: wsol_account.to_account_info(),
+        })
+    )
+}
+
+// Closes the wSOL account, returning its whole balance and rent to the owner as SOL. Callers
+// only do so when the account was empty before the instruction, so wSOL the owner already held
+// is never swept along with it
+pub fn unwrap_sol<'info>(
+    owner: &Signer<'info>,
+    wsol_account: &InterfaceAccount<'info, TokenInterAccount>,
+    token_program: &Interface<'info, TokenInterface>
+) -> Result<()> {
+    close_account(
+        CpiContext::new(token_program.to_account_info(), CloseAccount {
+            account: wsol_account.to_account_info(),
+            destination: owner.to_account_info(),
+            authority: owner.to_account_info(),
+        })
+    )
+}
@@ -0,0 +1,259 @@
+//! Compute units consumed by each instruction, compared against `compute_units.json`.
+//!
+//! `cargo bench -p pumpfun-tests` writes `target/compute_units.md` and fails if any instruction
+//! uses more than `CU_THRESHOLD_PERCENT` (default 5) percent over its baseline, or has no
+//! baseline at all. Set `UPDATE_BASELINE=1` to accept the current numbers instead.
+
+use std::{ collections::BTreeMap, fs, path::PathBuf, process::ExitCode };
+
+use pumpfun_client::{ instructions, FeeRecipient, PresaleConfig };
+use pumpfun_tests::*;
+use solana_sdk::{
+    clock::Clock,
+    instruction::Instruction,
+    keccak,
+    pubkey::Pubkey,
+    signature::{ Keypair, Signer },
+};
+
+const DEFAULT_THRESHOLD_PERCENT: f64 = 5.0;
+const PRESALE_ALLOCATION: u64 = 2_000_000_000;
+
+type Measurements = BTreeMap<String, u64>;
+
+struct Bench {
+    measurements: Measurements,
+}
+
+impl Bench {
+    fn record(&mut self, name: &str, result: SendResult) {
+        let outcome = result.unwrap_or_else(|failed|
+            panic!("{name} failed: {:?}, logs: {:#?}", failed.err, failed.meta.logs)
+        );
+        self.measurements.insert(name.to_string(), outcome.meta.compute_units_consumed);
+    }
+
+    fn record_as_admin(&mut self, name: &str, ctx: &mut TestContext, instruction: Instruction) {
+        self.record(name, ctx.send_as_admin(instruction));
+    }
+}
+
+fn measure() -> Measurements {
+    let mut bench = Bench { measurements: Measurements::new() };
+
+    let mut ctx = TestContext::bare();
+    let (admin, fee_wallet, base_token_mint) = (ctx.admin.pubkey(), ctx.fee_wallet, ctx.base_token_mint);
+    bench.record_as_admin(
+        "initialize",
+        &mut ctx,
+        instructions::initialize(&admin, &fee_wallet, RAYDIUM_MIGRATION_FEE)
+    );
+    bench.record_as_admin(
+        "add_base_token",
+        &mut ctx,
+        instructions::add_base_token(
+            &admin,
+            &base_token_mint,
+            SWAP_FEE,
+            BONDING_CURVE_LIMITATION,
+            INITIAL_VIRTUAL_BASE,
+            INITIAL_VIRTUAL_QUOTE,
+            CREATE_POOL_FEE
+        )
+    );
+    bench.record_as_admin(
+        "set_swap_fee",
+        &mut ctx,
+        instructions::set_swap_fee(&admin, &base_token_mint, SWAP_FEE)
+    );
+    bench.record_as_admin(
+        "set_bonding_curve_limitaion",
+        &mut ctx,
+        instructions::set_bonding_curve_limitaion(&admin, &base_token_mint, BONDING_CURVE_LIMITATION)
+    );
+    bench.record_as_admin(
+        "set_creator_fee_share",
+        &mut ctx,
+        instructions::set_creator_fee_share(&admin, 3000)
+    );
+    bench.record_as_admin(
+        "set_referral_fee_share",
+        &mut ctx,
+        instructions::set_referral_fee_share(&admin, 2000)
+    );
+    bench.record_as_admin(
+        "set_fee_recipients",
+        &mut ctx,
+        instructions::set_fee_recipients(&admin, vec![FeeRecipient {
+            wallet: fee_wallet,
+            weight: 1,
+        }])
+    );
+    bench.record_as_admin(
+        "register_referrer",
+        &mut ctx,
+        instructions::register_referrer(&admin, &Pubkey::new_unique())
+    );
+
+    let amm_config = ctx.load_cp_swap();
+    // Observations with a zero timestamp are treated as unwritten
+    ctx.warp_forward(1_000);
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = Keypair::new();
+    let result = ctx.create_pool_with(&creator, &mint, 1_000_000_000);
+    bench.record("create_pool", result);
+    let mint = mint.pubkey();
+
+    let trader = ctx.new_user(200_000_000_000);
+    let result = ctx.buy(&trader, &mint, 2_000_000_000);
+    bench.record("buy", result);
+    let sell_amount = ctx.token_balance(&ctx.quote_token_ata(&trader.pubkey(), &mint)) / 2;
+    let result = ctx.sell(&trader, &mint, sell_amount);
+    bench.record("sell", result);
+
+    bench.record_as_admin(
+        "quote_buy",
+        &mut ctx,
+        instructions::quote_buy(&mint, &base_token_mint, 2_000_000_000)
+    );
+    bench.record_as_admin(
+        "quote_sell",
+        &mut ctx,
+        instructions::quote_sell(&mint, &base_token_mint, sell_amount)
+    );
+    ctx.warp_forward(10);
+    bench.record_as_admin("get_twap", &mut ctx, instructions::get_twap(&mint, 5));
+
+    let result = ctx.send(
+        &[
+            instructions::claim_creator_fees(
+                &creator.pubkey(),
+                &mint,
+                &base_token_mint,
+                &anchor_spl::token::ID
+            ),
+        ],
+        &[&creator]
+    );
+    bench.record("claim_creator_fees", result);
+    ctx.create_base_token_ata(&fee_wallet);
+    bench.record_as_admin(
+        "withdraw_protocol_fees",
+        &mut ctx,
+        instructions::withdraw_protocol_fees(&admin, &base_token_mint, &anchor_spl::token::ID, &[
+            fee_wallet,
+        ])
+    );
+    let result = ctx.send(
+        &[
+            instructions::update_token_metadata(
+                &creator.pubkey(),
+                &mint,
+                &anchor_spl::token::ID,
+                "Renamed".to_string(),
+                "RNM".to_string(),
+                "https://example.com/renamed.json".to_string()
+            ),
+        ],
+        &[&creator]
+    );
+    bench.record("update_token_metadata", result);
+
+    // A single leaf allowlist, its root is the leaf itself
+    let buyer = ctx.new_user(5_000_000_000);
+    let presale_mint = Keypair::new();
+    let mut params = ctx.create_pool_params(1_000_000_000);
+    params.presale = Some(PresaleConfig {
+        merkle_root: keccak
+            ::hashv(&[buyer.pubkey().as_ref(), &PRESALE_ALLOCATION.to_le_bytes()])
+            .to_bytes(),
+        end_time: ctx.svm.get_sysvar::<Clock>().unix_timestamp + 3600,
+    });
+    ctx.create_pool_with_params(&creator, Some(&presale_mint), &presale_mint.pubkey(), params).expect(
+        "launch with a presale"
+    );
+    let result = ctx.presale_buy(
+        &buyer,
+        &presale_mint.pubkey(),
+        PRESALE_ALLOCATION,
+        PRESALE_ALLOCATION,
+        vec![]
+    );
+    bench.record("presale_buy", result);
+
+    ctx.buy(&trader, &mint, BONDING_CURVE_LIMITATION).expect("complete the curve");
+    let keys = ctx.migrate_keys(&mint, &amm_config);
+    let admin = ctx.admin.insecure_clone();
+    let result = ctx.migrate(&keys, &admin);
+    bench.record("proxy_initialize", result);
+
+    bench.measurements
+}
+
+fn report(baseline: &Measurements, current: &Measurements, threshold: f64) -> (String, Vec<String>) {
+    let mut report = String::from(
+        "| Instruction | Baseline | Current | Change |\n| --- | ---: | ---: | ---: |\n"
+    );
+    let mut failures = Vec::new();
+
+    for (name, &units) in current {
+        let Some(&before) = baseline.get(name) else {
+            report.push_str(&format!("| `{name}` | - | {units} | new |\n"));
+            failures.push(format!("{name}: {units} CU, missing from the baseline"));
+            continue;
+        };
+        let change = if before == 0 {
+            0.0
+        } else {
+            ((units as f64) - (before as f64)) * 100.0 / (before as f64)
+        };
+        report.push_str(&format!("| `{name}` | {before} | {units} | {change:+.2}% |\n"));
+        if change > threshold {
+            failures.push(format!("{name}: {before} -> {units} CU ({change:+.2}%)"));
+        }
+    }
+    (report, failures)
+}
+
+fn main() -> ExitCode {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let baseline_path = manifest_dir.join("benches/compute_units.json");
+    let report_path = manifest_dir.join("../target/compute_units.md");
+    let threshold = std::env
+        ::var("CU_THRESHOLD_PERCENT")
+        .ok()
+        .map(|value| value.parse().expect("CU_THRESHOLD_PERCENT must be a number"))
+        .unwrap_or(DEFAULT_THRESHOLD_PERCENT);
+
+    let current = measure();
+
+    if std::env::var_os("UPDATE_BASELINE").is_some() {
+        let json = serde_json::to_string_pretty(&current).unwrap();
+        fs::write(&baseline_path, json + "\n").unwrap();
+        println!("baseline written to {}", baseline_path.display());
+        return ExitCode::SUCCESS;
+    }
+
+    let Ok(baseline) = fs::read_to_string(&baseline_path) else {
+        eprintln!(
+            "no baseline at {}, generate it with UPDATE_BASELINE=1 and commit it",
+            baseline_path.display()
+        );
+        return ExitCode::FAILURE;
+    };
+    let baseline: Measurements = serde_json::from_str(&baseline).expect("invalid baseline");
+    let (report, failures) = report(&baseline, &current, threshold);
+    print!("{report}");
+    fs::create_dir_all(report_path.parent().unwrap()).unwrap();
+    fs::write(&report_path, &report).unwrap();
+    println!("\nreport written to {}", report_path.display());
+
+    if failures.is_empty() {
+        return ExitCode::SUCCESS;
+    }
+    eprintln!("\ncompute units regressed by more than {threshold}% or have no baseline:");
+    for failure in &failures {
+        eprintln!("  {failure}");
+    }
+    ExitCode::FAILURE
+}
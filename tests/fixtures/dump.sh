@@ -0,0 +1,10 @@
+#!/usr/bin/env bash
+# Fetches the mainnet programs the integration tests load next to the program under test.
+# The binaries are tracked in git so the suite loads them without network access. Run this to
+# refresh them, then commit the result.
+set -euo pipefail
+cd "$(dirname "$0")"
+
+solana program dump -u m metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s mpl_token_metadata.so
+# The program is built against the devnet CP-Swap deployment (`devnet` feature of raydium-cp-swap)
+solana program dump -u d CPMDWBwJDtYax9qW7AyRuVC19Cc4L4Vcy4n2BHAbHkCW raydium_cp_swap.so
@@ -0,0 +1,513 @@
+//! Shared setup for the integration tests. Each test gets a fresh in-process SVM with the
+//! program loaded from `target/deploy` (run `anchor build` first) and the Metaplex program
+//! from `tests/fixtures`, which `tests/fixtures/dump.sh` refreshes.
+
+// `SendResult` carries LiteSVM's failed transaction metadata, logs included
+#![allow(clippy::result_large_err, clippy::new_without_default)]
+
+use std::path::PathBuf;
+
+use anchor_lang::{ AccountDeserialize, AccountSerialize, AnchorDeserialize, Discriminator };
+use anchor_spl::{
+    associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    metadata::mpl_token_metadata,
+    token_2022::spl_token_2022::{
+        extension::StateWithExtensions,
+        state::Account as Token2022Account,
+    },
+    token::spl_token::{
+        self,
+        instruction as token_instruction,
+        native_mint,
+        solana_program::program_option::COption,
+        state::{ Account as TokenAccount, AccountState, Mint },
+    },
+};
+use litesvm::{ types::{ FailedTransactionMetadata, TransactionMetadata }, LiteSVM };
+use pumpfun_client::{
+    instructions::{ self, CreatePoolParams, MigrateKeys, TradeKeys },
+    pda,
+    quote::BaseMintFee,
+    PROGRAM_ID,
+};
+use pumpfun_indexer::{ decode::decode_cpi_event, ProgramEvent };
+use pumpfun_smart_contract::errors::CustomError;
+use raydium_cp_swap::states::AmmConfig;
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{ Instruction, InstructionError },
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{ Keypair, Signer },
+    system_instruction,
+    transaction::{ Transaction, TransactionError },
+};
+
+pub const BASE_DECIMALS: u8 = 6;
+pub const SWAP_FEE: u64 = 100; // 1%
+pub const INITIAL_VIRTUAL_BASE: u64 = 30_000_000_000;
+pub const INITIAL_VIRTUAL_QUOTE: u64 = 1_073_000_000_000_000;
+pub const BONDING_CURVE_LIMITATION: u64 = 115_000_000_000;
+pub const CREATE_POOL_FEE: u64 = 1_000_000;
+pub const RAYDIUM_MIGRATION_FEE: u64 = 150_000_000;
+pub const RAYDIUM_CREATE_POOL_FEE: u64 = 100_000_000;
+pub const RAYDIUM_TRADE_FEE_RATE: u64 = 2500; // 0.25%, in millionths
+
+pub struct Outcome {
+    pub meta: TransactionMetadata,
+    pub events: Vec<ProgramEvent>,
+}
+
+pub type SendResult = Result<Outcome, FailedTransactionMetadata>;
+
+pub struct TestContext {
+    pub svm: LiteSVM,
+    pub admin: Keypair,
+    pub fee_wallet: Pubkey,
+    pub base_token_mint: Pubkey,
+}
+
+fn workspace_path(relative: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..").join(relative)
+}
+
+impl TestContext {
+    /// Fresh SVM with the program loaded but nothing initialized
+    pub fn bare() -> Self {
+        let mut svm = LiteSVM::new();
+
+        let program = std::env
+            ::var("PUMPFUN_PROGRAM_SO")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| workspace_path("target/deploy/pumpfun_smart_contract.so"));
+        svm.add_program_from_file(PROGRAM_ID, &program).unwrap_or_else(|err|
+            panic!("failed to load {}: {err}, run `anchor build` first", program.display())
+        );
+        let metadata_program = workspace_path("tests/fixtures/mpl_token_metadata.so");
+        svm.add_program_from_file(mpl_token_metadata::ID, &metadata_program).unwrap_or_else(|err|
+            panic!(
+                "failed to load {}: {err}, fetch it with tests/fixtures/dump.sh and commit it",
+                metadata_program.display()
+            )
+        );
+
+        let admin = Keypair::new();
+        svm.airdrop(&admin.pubkey(), 100_000_000_000).unwrap();
+
+        let mut ctx = Self {
+            svm,
+            admin,
+            fee_wallet: Pubkey::new_unique(),
+            base_token_mint: Pubkey::default(),
+        };
+        ctx.base_token_mint = ctx.create_mint(BASE_DECIMALS);
+        ctx
+    }
+
+    /// Global configuration and one base token set up with the default parameters
+    pub fn new() -> Self {
+        let mut ctx = Self::bare();
+        ctx.configure();
+        ctx
+    }
+
+    /// Like `new`, with wrapped SOL as the base token
+    pub fn native() -> Self {
+        let mut ctx = Self::bare();
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(Mint { decimals: 9, is_initialized: true, ..Default::default() }, &mut data).unwrap();
+        ctx.set_rent_exempt_account(&native_mint::ID, data, spl_token::ID);
+        ctx.base_token_mint = native_mint::ID;
+        ctx.configure();
+        ctx
+    }
+
+    /// Initializes the global configuration with `fee_wallet` and adds the base token
+    pub fn configure(&mut self) {
+        let admin = self.admin.pubkey();
+        let fee_wallet = self.fee_wallet;
+        let base_token_mint = self.base_token_mint;
+        self.send_as_admin(instructions::initialize(&admin, &fee_wallet, RAYDIUM_MIGRATION_FEE))
+            .expect("initialize");
+        self.add_base_token(&base_token_mint);
+    }
+
+    /// Adds `base_token_mint` as a base token with the default parameters
+    pub fn add_base_token(&mut self, base_token_mint: &Pubkey) {
+        let admin = self.admin.pubkey();
+        self.send_as_admin(
+            instructions::add_base_token(
+                &admin,
+                base_token_mint,
+                SWAP_FEE,
+                BONDING_CURVE_LIMITATION,
+                INITIAL_VIRTUAL_BASE,
+                INITIAL_VIRTUAL_QUOTE,
+                CREATE_POOL_FEE
+            )
+        ).expect("add_base_token");
+    }
+
+    pub fn send(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> SendResult {
+        let svm = &mut self.svm;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&signers[0].pubkey()),
+            signers,
+            svm.latest_blockhash()
+        );
+        let keys = tx.message.account_keys.clone();
+        let result = svm.send_transaction(tx);
+        // Identical transactions in later steps would otherwise be rejected as duplicates
+        svm.expire_blockhash();
+
+        result.map(|meta| {
+            let events = meta.inner_instructions
+                .iter()
+                .flatten()
+                .filter(|inner| keys[inner.instruction.program_id_index as usize] == PROGRAM_ID)
+                .filter_map(|inner| decode_cpi_event(&inner.instruction.data).unwrap())
+                .collect();
+            Outcome { meta, events }
+        })
+    }
+
+    pub fn send_as_admin(&mut self, instruction: Instruction) -> SendResult {
+        let admin = self.admin.insecure_clone();
+        self.send(&[instruction], &[&admin])
+    }
+
+    pub fn new_user(&mut self, base_token_amount: u64) -> Keypair {
+        let user = Keypair::new();
+        self.svm.airdrop(&user.pubkey(), 10_000_000_000).unwrap();
+        if base_token_amount > 0 {
+            self.mint_base_token(&user.pubkey(), base_token_amount);
+        }
+        user
+    }
+
+    pub fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let admin = self.admin.insecure_clone();
+        let mint = Keypair::new();
+        let rent = self.svm.minimum_balance_for_rent_exemption(Mint::LEN);
+        let instructions = [
+            system_instruction::create_account(
+                &admin.pubkey(),
+                &mint.pubkey(),
+                rent,
+                Mint::LEN as u64,
+                &spl_token::ID
+            ),
+            token_instruction
+                ::initialize_mint2(&spl_token::ID, &mint.pubkey(), &admin.pubkey(), None, decimals)
+                .unwrap(),
+        ];
+        self.send(&instructions, &[&admin, &mint]).expect("create mint");
+        mint.pubkey()
+    }
+
+    pub fn mint_base_token(&mut self, owner: &Pubkey, amount: u64) {
+        let admin = self.admin.insecure_clone();
+        let mint = self.base_token_mint;
+        let ata = self.base_token_ata(owner);
+        let instructions = [
+            create_associated_token_account_idempotent(&admin.pubkey(), owner, &mint, &spl_token::ID),
+            token_instruction
+                ::mint_to(&spl_token::ID, &mint, &ata, &admin.pubkey(), &[], amount)
+                .unwrap(),
+        ];
+        self.send(&instructions, &[&admin]).expect("mint base token");
+    }
+
+    pub fn base_token_ata(&self, owner: &Pubkey) -> Pubkey {
+        pda::associated_token_address(owner, &self.base_token_mint, &spl_token::ID)
+    }
+
+    /// Empty base token account, e.g. for a referrer or fee recipient that never traded
+    pub fn create_base_token_ata(&mut self, owner: &Pubkey) -> Pubkey {
+        let admin = self.admin.insecure_clone();
+        let mint = self.base_token_mint;
+        self.send(
+            &[create_associated_token_account_idempotent(&admin.pubkey(), owner, &mint, &spl_token::ID)],
+            &[&admin]
+        ).expect("create base token account");
+        self.base_token_ata(owner)
+    }
+
+    /// Wraps `lamports` of the owner's SOL into its wSOL account, creating it if needed
+    pub fn wrap_sol(&mut self, owner: &Keypair, lamports: u64) -> Pubkey {
+        let ata = self.base_token_ata(&owner.pubkey());
+        let instructions = [
+            create_associated_token_account_idempotent(
+                &owner.pubkey(),
+                &owner.pubkey(),
+                &native_mint::ID,
+                &spl_token::ID
+            ),
+            system_instruction::transfer(&owner.pubkey(), &ata, lamports),
+            token_instruction::sync_native(&spl_token::ID, &ata).unwrap(),
+        ];
+        self.send(&instructions, &[owner]).expect("wrap sol");
+        ata
+    }
+
+    /// Token program owning a launched mint, spl-token until the mint exists
+    pub fn quote_token_program(&self, mint_address: &Pubkey) -> Pubkey {
+        self.svm
+            .get_account(mint_address)
+            .map(|account| account.owner)
+            .unwrap_or(spl_token::ID)
+    }
+
+    pub fn quote_token_ata(&self, owner: &Pubkey, mint_address: &Pubkey) -> Pubkey {
+        pda::associated_token_address(owner, mint_address, &self.quote_token_program(mint_address))
+    }
+
+    pub fn create_quote_token_ata(&mut self, owner: &Pubkey, mint_address: &Pubkey) -> Pubkey {
+        let admin = self.admin.insecure_clone();
+        let token_program = self.quote_token_program(mint_address);
+        self.send(
+            &[
+                create_associated_token_account_idempotent(
+                    &admin.pubkey(),
+                    owner,
+                    mint_address,
+                    &token_program
+                ),
+            ],
+            &[&admin]
+        ).expect("create quote token account");
+        self.quote_token_ata(owner, mint_address)
+    }
+
+    /// Zero for accounts that don't exist, works for spl-token and Token-2022 accounts
+    pub fn token_balance(&self, address: &Pubkey) -> u64 {
+        self.svm
+            .get_account(address)
+            .map(|account| {
+                StateWithExtensions::<Token2022Account>::unpack(&account.data).unwrap().base.amount
+            })
+            .unwrap_or_default()
+    }
+
+    /// Transfer fee of the base token mint in the current epoch
+    pub fn base_mint_fee(&self) -> Option<BaseMintFee> {
+        let account = self.svm.get_account(&self.base_token_mint).unwrap();
+        BaseMintFee::from_mint_data(&account.data, self.svm.get_sysvar::<Clock>().epoch).unwrap()
+    }
+
+    pub fn fetch<T: AccountDeserialize + Discriminator>(&self, address: &Pubkey) -> T {
+        let account = self.svm
+            .get_account(address)
+            .unwrap_or_else(|| panic!("account {address} does not exist"));
+        pumpfun_client::accounts::decode(&account.data).unwrap()
+    }
+
+    pub fn warp_forward(&mut self, seconds: i64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += seconds;
+        clock.slot += 1;
+        self.svm.set_sysvar(&clock);
+    }
+
+    pub fn trade_keys(&self, payer: &Pubkey, mint_address: &Pubkey) -> TradeKeys {
+        TradeKeys {
+            payer: *payer,
+            mint_address: *mint_address,
+            base_token_mint: self.base_token_mint,
+            token_program: spl_token::ID,
+            quote_token_program: self.quote_token_program(mint_address),
+            referrer: None,
+        }
+    }
+
+    pub fn create_pool_params(&self, dev_buy_amount: u64) -> CreatePoolParams {
+        CreatePoolParams {
+            name: "Test Token".to_string(),
+            symbol: "TEST".to_string(),
+            uri: "https://example.com/test.json".to_string(),
+            dev_buy_amount,
+            initial_token_transfer_percent: 0,
+            presale: None,
+            use_token_2022: false,
+            mint_nonce: None,
+        }
+    }
+
+    /// Launches a Metaplex token with `creator` as the payer and returns its mint
+    pub fn create_pool(&mut self, creator: &Keypair, dev_buy_amount: u64) -> Pubkey {
+        let mint = Keypair::new();
+        self.create_pool_with(creator, &mint, dev_buy_amount).expect("create_pool");
+        mint.pubkey()
+    }
+
+    pub fn create_pool_with(
+        &mut self,
+        creator: &Keypair,
+        mint: &Keypair,
+        dev_buy_amount: u64
+    ) -> SendResult {
+        let params = self.create_pool_params(dev_buy_amount);
+        self.create_pool_with_params(creator, Some(mint), &mint.pubkey(), params)
+    }
+
+    /// `mint_signer` is the mint keypair, or `None` for mints derived from `params.mint_nonce`
+    pub fn create_pool_with_params(
+        &mut self,
+        creator: &Keypair,
+        mint_signer: Option<&Keypair>,
+        mint_address: &Pubkey,
+        params: CreatePoolParams
+    ) -> SendResult {
+        let ix = instructions::create_pool(
+            &creator.pubkey(),
+            mint_address,
+            &self.base_token_mint,
+            &spl_token::ID,
+            &self.fee_wallet,
+            params
+        );
+        let mut signers = vec![creator];
+        signers.extend(mint_signer);
+        self.send(
+            &[ComputeBudgetInstruction::set_compute_unit_limit(600_000), ix],
+            &signers
+        )
+    }
+
+    pub fn buy(&mut self, user: &Keypair, mint_address: &Pubkey, base_input_amount: u64) -> SendResult {
+        let keys = self.trade_keys(&user.pubkey(), mint_address);
+        self.send(&[instructions::buy(&keys, base_input_amount, 0)], &[user])
+    }
+
+    pub fn presale_buy(
+        &mut self,
+        user: &Keypair,
+        mint_address: &Pubkey,
+        base_input_amount: u64,
+        allocation: u64,
+        proof: Vec<[u8; 32]>
+    ) -> SendResult {
+        let keys = self.trade_keys(&user.pubkey(), mint_address);
+        self.send(
+            &[instructions::presale_buy(&keys, base_input_amount, 0, allocation, proof)],
+            &[user]
+        )
+    }
+
+    /// Runs a read-only instruction and decodes what it passed to `set_return_data`
+    pub fn simulate_return<T: AnchorDeserialize>(&mut self, instruction: Instruction) -> T {
+        let payer = self.admin.insecure_clone();
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[&payer],
+            self.svm.latest_blockhash()
+        );
+        let simulated = self.svm
+            .simulate_transaction(tx)
+            .unwrap_or_else(|failed| panic!("simulation failed: {:?}, logs: {:#?}", failed.err, failed.meta.logs));
+        T::try_from_slice(&simulated.meta.return_data.data).unwrap()
+    }
+
+    /// Loads Raydium CP-Swap with a fee tier and its create pool fee receiver, returns the
+    /// `AmmConfig` address
+    pub fn load_cp_swap(&mut self) -> Pubkey {
+        let program = workspace_path("tests/fixtures/raydium_cp_swap.so");
+        self.svm.add_program_from_file(raydium_cp_swap::ID, &program).unwrap_or_else(|err|
+            panic!("failed to load {}: {err}, fetch it with tests/fixtures/dump.sh and commit it", program.display())
+        );
+
+        let index = 0u16;
+        let (amm_config, bump) = Pubkey::find_program_address(
+            &[b"amm_config", &index.to_be_bytes()],
+            &raydium_cp_swap::ID
+        );
+        let config = AmmConfig {
+            bump,
+            index,
+            trade_fee_rate: RAYDIUM_TRADE_FEE_RATE,
+            protocol_fee_rate: 120_000,
+            fund_fee_rate: 40_000,
+            create_pool_fee: RAYDIUM_CREATE_POOL_FEE,
+            protocol_owner: self.admin.pubkey(),
+            fund_owner: self.admin.pubkey(),
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        config.try_serialize(&mut data).unwrap();
+        self.set_rent_exempt_account(&amm_config, data, raydium_cp_swap::ID);
+
+        let rent = self.svm.minimum_balance_for_rent_exemption(TokenAccount::LEN);
+        let mut data = vec![0; TokenAccount::LEN];
+        TokenAccount::pack(
+            TokenAccount {
+                mint: native_mint::ID,
+                owner: Pubkey::new_unique(),
+                state: AccountState::Initialized,
+                is_native: COption::Some(rent),
+                ..Default::default()
+            },
+            &mut data
+        ).unwrap();
+        self.set_rent_exempt_account(&raydium_cp_swap::create_pool_fee_reveiver::ID, data, spl_token::ID);
+
+        amm_config
+    }
+
+    fn set_rent_exempt_account(&mut self, address: &Pubkey, data: Vec<u8>, owner: Pubkey) {
+        let account = Account {
+            lamports: self.svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.svm.set_account(*address, account).unwrap();
+    }
+
+    /// Migration keys with the admin as migration authority
+    pub fn migrate_keys(&self, mint_address: &Pubkey, amm_config: &Pubkey) -> MigrateKeys {
+        MigrateKeys {
+            migration_authority: self.admin.pubkey(),
+            mint_address: *mint_address,
+            base_token_mint: self.base_token_mint,
+            token_program: spl_token::ID,
+            quote_token_program: spl_token::ID,
+            amm_config: *amm_config,
+            create_pool_fee: raydium_cp_swap::create_pool_fee_reveiver::ID,
+        }
+    }
+
+    pub fn migrate(&mut self, keys: &MigrateKeys, signer: &Keypair) -> SendResult {
+        self.send(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(600_000),
+                instructions::proxy_initialize(keys),
+            ],
+            &[signer]
+        )
+    }
+
+    pub fn sell(&mut self, user: &Keypair, mint_address: &Pubkey, quote_input_amount: u64) -> SendResult {
+        let keys = self.trade_keys(&user.pubkey(), mint_address);
+        self.send(&[instructions::sell(&keys, quote_input_amount, 0)], &[user])
+    }
+}
+
+/// Asserts that a transaction failed with one of the program's errors
+pub fn assert_custom_error(result: SendResult, expected: CustomError) {
+    let code = u32::from(expected);
+    match result {
+        Ok(_) => panic!("expected {expected:?}, the transaction succeeded"),
+        Err(failed) =>
+            match failed.err {
+                TransactionError::InstructionError(_, InstructionError::Custom(actual)) => {
+                    assert_eq!(actual, code, "expected {expected:?}, logs: {:#?}", failed.meta.logs)
+                }
+                err => panic!("expected {expected:?}, got {err:?}, logs: {:#?}", failed.meta.logs),
+            }
+    }
+}
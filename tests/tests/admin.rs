@@ -0,0 +1,121 @@
+use pumpfun_client::{ instructions, pda, BaseTokenConfiguration, FeeRecipient, InitializeConfiguration };
+use pumpfun_smart_contract::errors::CustomError;
+use pumpfun_tests::*;
+use solana_sdk::{ pubkey::Pubkey, signature::Signer };
+
+#[test]
+fn initialize_sets_admin_and_fee_wallet() {
+    let ctx = TestContext::new();
+
+    let config: InitializeConfiguration = ctx.fetch(&pda::global_configuration());
+    assert_eq!(config.admin, ctx.admin.pubkey());
+    assert_eq!(config.migration_authority, ctx.admin.pubkey());
+    assert_eq!(config.fee_wallet, ctx.fee_wallet);
+    assert_eq!(config.raydium_migration_fee, RAYDIUM_MIGRATION_FEE);
+    assert_eq!(config.creator_fee_share, 0);
+    assert_eq!(config.referral_fee_share, 0);
+}
+
+#[test]
+fn initialize_runs_only_once() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin.pubkey();
+
+    let result = ctx.send_as_admin(instructions::initialize(&admin, &Pubkey::new_unique(), 0));
+    assert!(result.is_err());
+}
+
+#[test]
+fn add_base_token_stores_curve_parameters() {
+    let ctx = TestContext::new();
+
+    let config: BaseTokenConfiguration = ctx.fetch(
+        &pda::base_token_configuration(&ctx.base_token_mint)
+    );
+    assert_eq!(config.base_token_mint, ctx.base_token_mint);
+    assert_eq!(config.swap_fee, SWAP_FEE);
+    assert_eq!(config.bonding_curve_limitation, BONDING_CURVE_LIMITATION);
+    assert_eq!(config.initial_virtual_base_token, INITIAL_VIRTUAL_BASE);
+    assert_eq!(config.initial_virtual_quote_token, INITIAL_VIRTUAL_QUOTE);
+    assert_eq!(config.create_pool_fee_amount, CREATE_POOL_FEE);
+}
+
+#[test]
+fn base_token_setters_update_configuration() {
+    let mut ctx = TestContext::new();
+    let (admin, base_token_mint) = (ctx.admin.pubkey(), ctx.base_token_mint);
+
+    ctx.send_as_admin(instructions::set_swap_fee(&admin, &base_token_mint, 250)).unwrap();
+    ctx.send_as_admin(
+        instructions::set_bonding_curve_limitaion(&admin, &base_token_mint, 200_000_000_000)
+    ).unwrap();
+
+    let config: BaseTokenConfiguration = ctx.fetch(&pda::base_token_configuration(&base_token_mint));
+    assert_eq!(config.swap_fee, 250);
+    assert_eq!(config.bonding_curve_limitation, 200_000_000_000);
+}
+
+#[test]
+fn global_setters_update_configuration() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin.pubkey();
+    let recipients = vec![
+        FeeRecipient { wallet: Pubkey::new_unique(), weight: 3 },
+        FeeRecipient { wallet: Pubkey::new_unique(), weight: 1 }
+    ];
+
+    ctx.send_as_admin(instructions::set_creator_fee_share(&admin, 3000)).unwrap();
+    ctx.send_as_admin(instructions::set_referral_fee_share(&admin, 2000)).unwrap();
+    ctx.send_as_admin(instructions::set_fee_recipients(&admin, recipients.clone())).unwrap();
+
+    let config: InitializeConfiguration = ctx.fetch(&pda::global_configuration());
+    assert_eq!(config.creator_fee_share, 3000);
+    assert_eq!(config.referral_fee_share, 2000);
+    assert_eq!(config.fee_recipients[0].wallet, recipients[0].wallet);
+    assert_eq!(config.fee_recipients[1].weight, 1);
+    assert_eq!(config.fee_recipients[2].weight, 0);
+    assert_eq!(config.total_fee_recipient_weight().unwrap(), 4);
+}
+
+#[test]
+fn setters_reject_non_admin() {
+    let mut ctx = TestContext::new();
+    let intruder = ctx.new_user(0);
+    let base_token_mint = ctx.base_token_mint;
+
+    let result = ctx.send(
+        &[instructions::set_swap_fee(&intruder.pubkey(), &base_token_mint, 0)],
+        &[&intruder]
+    );
+    assert_custom_error(result, CustomError::InvalidAdminAccount);
+
+    let result = ctx.send(
+        &[instructions::set_creator_fee_share(&intruder.pubkey(), 10000)],
+        &[&intruder]
+    );
+    assert_custom_error(result, CustomError::InvalidAdminAccount);
+}
+
+#[test]
+fn fee_shares_cannot_exceed_platform_fee() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin.pubkey();
+
+    ctx.send_as_admin(instructions::set_creator_fee_share(&admin, 6000)).unwrap();
+    let result = ctx.send_as_admin(instructions::set_referral_fee_share(&admin, 5000));
+    assert_custom_error(result, CustomError::InvalidFeeShare);
+}
+
+#[test]
+fn fee_recipients_need_a_positive_weight() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin.pubkey();
+
+    let result = ctx.send_as_admin(
+        instructions::set_fee_recipients(&admin, vec![FeeRecipient {
+            wallet: Pubkey::new_unique(),
+            weight: 0,
+        }])
+    );
+    assert_custom_error(result, CustomError::InvalidFeeRecipients);
+}
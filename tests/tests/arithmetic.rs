@@ -0,0 +1,127 @@
+use pumpfun_client::{ instructions, FeeRecipient };
+use pumpfun_smart_contract::errors::CustomError;
+use pumpfun_tests::*;
+use solana_sdk::{ pubkey::Pubkey, signature::{ Keypair, Signer } };
+
+const DEV_BUY: u64 = 1_000_000_000;
+
+fn set_swap_fee(ctx: &mut TestContext, swap_fee: u64) {
+    let (admin, base_token_mint) = (ctx.admin.pubkey(), ctx.base_token_mint);
+    ctx.send_as_admin(instructions::set_swap_fee(&admin, &base_token_mint, swap_fee)).unwrap();
+}
+
+#[test]
+fn create_pool_rejects_overflowing_required_base_token() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+
+    let result = ctx.create_pool_with(&creator, &Keypair::new(), u64::MAX);
+    assert_custom_error(result, CustomError::MathOverflow);
+}
+
+#[test]
+fn swap_fee_is_capped_at_100_percent() {
+    let mut ctx = TestContext::bare();
+    let (admin, fee_wallet, base_token_mint) = (ctx.admin.pubkey(), ctx.fee_wallet, ctx.base_token_mint);
+    ctx.send_as_admin(instructions::initialize(&admin, &fee_wallet, 0)).unwrap();
+    let add_base_token = |swap_fee| {
+        instructions::add_base_token(
+            &admin,
+            &base_token_mint,
+            swap_fee,
+            BONDING_CURVE_LIMITATION,
+            INITIAL_VIRTUAL_BASE,
+            INITIAL_VIRTUAL_QUOTE,
+            CREATE_POOL_FEE
+        )
+    };
+
+    let result = ctx.send_as_admin(add_base_token(10001));
+    assert_custom_error(result, CustomError::InvalidSwapFee);
+    ctx.send_as_admin(add_base_token(10000)).unwrap();
+
+    for swap_fee in [10001, u64::MAX] {
+        let result = ctx.send_as_admin(instructions::set_swap_fee(&admin, &base_token_mint, swap_fee));
+        assert_custom_error(result, CustomError::InvalidSwapFee);
+    }
+}
+
+#[test]
+fn buy_rejects_overflowing_base_reserves() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(5_000_000_000);
+    set_swap_fee(&mut ctx, 0);
+
+    // The curve math rejects the amount before the trader's transfer could fail for lack of funds
+    let result = ctx.buy(&trader, &mint, u64::MAX);
+    assert_custom_error(result, CustomError::MathOverflow);
+    assert_eq!(ctx.token_balance(&ctx.base_token_ata(&trader.pubkey())), 5_000_000_000);
+    assert!(ctx.svm.get_account(&ctx.quote_token_ata(&trader.pubkey(), &mint)).is_none());
+}
+
+#[test]
+fn sell_rejects_payout_above_base_reserves() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(5_000_000_000);
+    ctx.buy(&trader, &mint, 2_000_000_000).unwrap();
+
+    let result = ctx.sell(&trader, &mint, u64::MAX);
+    assert_custom_error(result, CustomError::MathUnderflow);
+}
+
+#[test]
+fn fee_share_setters_reject_overflowing_totals() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin.pubkey();
+    ctx.send_as_admin(instructions::set_referral_fee_share(&admin, 100)).unwrap();
+
+    let result = ctx.send_as_admin(instructions::set_creator_fee_share(&admin, u64::MAX));
+    assert_custom_error(result, CustomError::MathOverflow);
+}
+
+#[test]
+fn fee_recipient_weights_must_not_overflow() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin.pubkey();
+
+    let result = ctx.send_as_admin(
+        instructions::set_fee_recipients(&admin, vec![
+            FeeRecipient { wallet: Pubkey::new_unique(), weight: u64::MAX },
+            FeeRecipient { wallet: Pubkey::new_unique(), weight: 1 }
+        ])
+    );
+    assert_custom_error(result, CustomError::MathOverflow);
+}
+
+#[test]
+fn migration_rejects_overflowing_lamports_required() {
+    let mut ctx = TestContext::bare();
+    let (admin, fee_wallet, base_token_mint) = (ctx.admin.pubkey(), ctx.fee_wallet, ctx.base_token_mint);
+    ctx.send_as_admin(instructions::initialize(&admin, &fee_wallet, u64::MAX)).unwrap();
+    ctx.send_as_admin(
+        instructions::add_base_token(
+            &admin,
+            &base_token_mint,
+            SWAP_FEE,
+            BONDING_CURVE_LIMITATION,
+            INITIAL_VIRTUAL_BASE,
+            INITIAL_VIRTUAL_QUOTE,
+            CREATE_POOL_FEE
+        )
+    ).unwrap();
+    let amm_config = ctx.load_cp_swap();
+
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let whale = ctx.new_user(200_000_000_000);
+    ctx.buy(&whale, &mint, BONDING_CURVE_LIMITATION).unwrap();
+
+    let keys = ctx.migrate_keys(&mint, &amm_config);
+    let admin = ctx.admin.insecure_clone();
+    let result = ctx.migrate(&keys, &admin);
+    assert_custom_error(result, CustomError::MathOverflow);
+}
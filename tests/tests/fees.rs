@@ -0,0 +1,218 @@
+use pumpfun_client::{ instructions, pda, FeeRecipient, Referrer };
+use pumpfun_indexer::ProgramEvent;
+use pumpfun_smart_contract::errors::CustomError;
+use pumpfun_tests::*;
+use solana_sdk::{ pubkey::Pubkey, signature::Signer };
+
+const DEV_BUY: u64 = 1_000_000_000;
+const TRADE: u64 = 2_000_000_000;
+
+fn protocol_fee_vault_ata(ctx: &TestContext) -> Pubkey {
+    ctx.base_token_ata(&pda::protocol_fee_vault(&ctx.base_token_mint))
+}
+
+#[test]
+fn creator_claims_its_share_of_the_platform_fee() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin.pubkey();
+    ctx.send_as_admin(instructions::set_creator_fee_share(&admin, 3000)).unwrap();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(5_000_000_000);
+
+    ctx.buy(&trader, &mint, TRADE).unwrap();
+
+    let creator_fee = (((TRADE * SWAP_FEE) / 10000) * 3000) / 10000;
+    let creator_vault_ata = ctx.base_token_ata(&pda::creator_vault(&mint));
+    assert_eq!(ctx.token_balance(&creator_vault_ata), creator_fee);
+
+    let before = ctx.token_balance(&ctx.base_token_ata(&creator.pubkey()));
+    let base_token_mint = ctx.base_token_mint;
+    let claim = instructions::claim_creator_fees(
+        &creator.pubkey(),
+        &mint,
+        &base_token_mint,
+        &anchor_spl::token::ID
+    );
+    let outcome = ctx.send(std::slice::from_ref(&claim), &[&creator]).unwrap();
+
+    assert_eq!(ctx.token_balance(&ctx.base_token_ata(&creator.pubkey())), before + creator_fee);
+    assert_eq!(ctx.token_balance(&creator_vault_ata), 0);
+    let claimed = outcome.events.iter().find_map(|event| match event {
+        ProgramEvent::CreatorFeesClaimed(claimed) => Some(claimed),
+        _ => None,
+    });
+    let claimed = claimed.expect("no CreatorFeesClaimed emitted");
+    assert_eq!((claimed.mint_address, claimed.creator, claimed.amount), (mint, creator.pubkey(), creator_fee));
+
+    let result = ctx.send(&[claim], &[&creator]);
+    assert_custom_error(result, CustomError::NothingToClaim);
+}
+
+#[test]
+fn only_the_creator_claims_creator_fees() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin.pubkey();
+    ctx.send_as_admin(instructions::set_creator_fee_share(&admin, 3000)).unwrap();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(5_000_000_000);
+    ctx.buy(&trader, &mint, TRADE).unwrap();
+
+    let base_token_mint = ctx.base_token_mint;
+    let result = ctx.send(
+        &[
+            instructions::claim_creator_fees(
+                &trader.pubkey(),
+                &mint,
+                &base_token_mint,
+                &anchor_spl::token::ID
+            ),
+        ],
+        &[&trader]
+    );
+    assert_custom_error(result, CustomError::InvalidCreator);
+}
+
+#[test]
+fn referrer_earns_its_share_on_buys_and_sells() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin.pubkey();
+    let referrer = Pubkey::new_unique();
+    ctx.send_as_admin(instructions::register_referrer(&admin, &referrer)).unwrap();
+    ctx.send_as_admin(instructions::set_referral_fee_share(&admin, 2000)).unwrap();
+    let referrer_ata = ctx.create_base_token_ata(&referrer);
+
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(5_000_000_000);
+    let mut keys = ctx.trade_keys(&trader.pubkey(), &mint);
+    keys.referrer = Some(referrer);
+
+    let protocol_fees_before = ctx.token_balance(&protocol_fee_vault_ata(&ctx));
+    ctx.send(&[instructions::buy(&keys, TRADE, 0)], &[&trader]).unwrap();
+
+    let platform_fee = (TRADE * SWAP_FEE) / 10000;
+    let referral_fee = (platform_fee * 2000) / 10000;
+    assert_eq!(ctx.token_balance(&referrer_ata), referral_fee);
+    assert_eq!(
+        ctx.token_balance(&protocol_fee_vault_ata(&ctx)) - protocol_fees_before,
+        platform_fee - referral_fee
+    );
+
+    let bought = ctx.token_balance(&ctx.quote_token_ata(&trader.pubkey(), &mint));
+    ctx.send(&[instructions::sell(&keys, bought, 0)], &[&trader]).unwrap();
+    assert!(ctx.token_balance(&referrer_ata) > referral_fee);
+
+    let account: Referrer = ctx.fetch(&pda::referrer(&referrer));
+    assert_eq!(account.referred_trades, 2);
+    assert_eq!(account.total_earned, ctx.token_balance(&referrer_ata));
+}
+
+#[test]
+fn referral_fee_goes_to_the_referrer_wallet_only() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin.pubkey();
+    let referrer = Pubkey::new_unique();
+    ctx.send_as_admin(instructions::register_referrer(&admin, &referrer)).unwrap();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(5_000_000_000);
+
+    let mut keys = ctx.trade_keys(&trader.pubkey(), &mint);
+    keys.referrer = Some(referrer);
+    let mut ix = instructions::buy(&keys, TRADE, 0);
+    // Swap the referrer's payout account for the trader's own
+    let referrer_ata = ctx.base_token_ata(&referrer);
+    let trader_ata = ctx.base_token_ata(&trader.pubkey());
+    for meta in ix.accounts.iter_mut().filter(|meta| meta.pubkey == referrer_ata) {
+        meta.pubkey = trader_ata;
+    }
+
+    let result = ctx.send(&[ix], &[&trader]);
+    assert_custom_error(result, CustomError::InvalidReferrer);
+}
+
+#[test]
+fn traders_cannot_refer_themselves() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin.pubkey();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(5_000_000_000);
+    ctx.send_as_admin(instructions::register_referrer(&admin, &trader.pubkey())).unwrap();
+    ctx.send_as_admin(instructions::set_referral_fee_share(&admin, 2000)).unwrap();
+
+    let mut keys = ctx.trade_keys(&trader.pubkey(), &mint);
+    keys.referrer = Some(trader.pubkey());
+    let result = ctx.send(&[instructions::buy(&keys, TRADE, 0)], &[&trader]);
+    assert_custom_error(result, CustomError::SelfReferral);
+
+    ctx.buy(&trader, &mint, TRADE).unwrap();
+    let bought = ctx.token_balance(&ctx.quote_token_ata(&trader.pubkey(), &mint));
+    let result = ctx.send(&[instructions::sell(&keys, bought, 0)], &[&trader]);
+    assert_custom_error(result, CustomError::SelfReferral);
+}
+
+#[test]
+fn protocol_fees_are_split_by_weight() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin.pubkey();
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    ctx.send_as_admin(
+        instructions::set_fee_recipients(&admin, vec![
+            FeeRecipient { wallet: first, weight: 3 },
+            FeeRecipient { wallet: second, weight: 1 }
+        ])
+    ).unwrap();
+    let first_ata = ctx.create_base_token_ata(&first);
+    let second_ata = ctx.create_base_token_ata(&second);
+
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(5_000_000_000);
+    ctx.buy(&trader, &mint, TRADE).unwrap();
+
+    let collected = ctx.token_balance(&protocol_fee_vault_ata(&ctx));
+    let base_token_mint = ctx.base_token_mint;
+    let outcome = ctx.send_as_admin(
+        instructions::withdraw_protocol_fees(&admin, &base_token_mint, &anchor_spl::token::ID, &[
+            first,
+            second,
+        ])
+    ).unwrap();
+
+    let first_share = (collected * 3) / 4;
+    assert_eq!(ctx.token_balance(&first_ata), first_share);
+    assert_eq!(ctx.token_balance(&second_ata), collected - first_share);
+    assert_eq!(ctx.token_balance(&protocol_fee_vault_ata(&ctx)), 0);
+    let withdrawn = outcome.events.iter().find_map(|event| match event {
+        ProgramEvent::ProtocolFeesWithdrawn(withdrawn) => Some(withdrawn),
+        _ => None,
+    });
+    let withdrawn = withdrawn.expect("no ProtocolFeesWithdrawn emitted");
+    assert_eq!(withdrawn.amount, collected);
+    assert_eq!(withdrawn.recipients, [first, second]);
+}
+
+#[test]
+fn protocol_withdrawal_needs_every_recipient() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin.pubkey();
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    ctx.send_as_admin(
+        instructions::set_fee_recipients(&admin, vec![
+            FeeRecipient { wallet: first, weight: 1 },
+            FeeRecipient { wallet: second, weight: 1 }
+        ])
+    ).unwrap();
+    ctx.create_base_token_ata(&first);
+    let creator = ctx.new_user(10_000_000_000);
+    ctx.create_pool(&creator, DEV_BUY);
+
+    let base_token_mint = ctx.base_token_mint;
+    let result = ctx.send_as_admin(
+        instructions::withdraw_protocol_fees(&admin, &base_token_mint, &anchor_spl::token::ID, &[first])
+    );
+    assert_custom_error(result, CustomError::InvalidFeeRecipients);
+}
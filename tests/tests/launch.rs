@@ -0,0 +1,157 @@
+use anchor_spl::{
+    metadata::mpl_token_metadata::accounts::Metadata,
+    token::spl_token::{ solana_program::program_option::COption, state::Mint },
+    token_2022::{
+        self,
+        spl_token_2022::{
+            extension::{ BaseStateWithExtensions, StateWithExtensions },
+            state::Mint as Token2022Mint,
+        },
+    },
+    token_2022_extensions::spl_token_metadata_interface::state::TokenMetadata,
+};
+use pumpfun_client::{ instructions, pda, BondingCurve };
+use pumpfun_indexer::ProgramEvent;
+use pumpfun_smart_contract::errors::CustomError;
+use pumpfun_tests::*;
+use solana_sdk::{ program_pack::Pack, signature::{ Keypair, Signer } };
+
+const DEV_BUY: u64 = 1_000_000_000;
+
+#[test]
+fn launch_revokes_mint_and_freeze_authority() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+
+    let account = ctx.svm.get_account(&mint).unwrap();
+    let state = Mint::unpack(&account.data).unwrap();
+    assert_eq!(state.mint_authority, COption::None);
+    assert_eq!(state.freeze_authority, COption::None);
+    assert_eq!(state.supply, INITIAL_VIRTUAL_QUOTE);
+}
+
+#[test]
+fn token_2022_launch_trades_and_embeds_metadata() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = Keypair::new();
+    let mut params = ctx.create_pool_params(DEV_BUY);
+    params.use_token_2022 = true;
+    ctx.create_pool_with_params(&creator, Some(&mint), &mint.pubkey(), params).unwrap();
+    let mint = mint.pubkey();
+
+    let account = ctx.svm.get_account(&mint).unwrap();
+    assert_eq!(account.owner, token_2022::ID);
+    let state = StateWithExtensions::<Token2022Mint>::unpack(&account.data).unwrap();
+    assert_eq!(state.base.mint_authority, COption::None);
+    let metadata = state.get_variable_len_extension::<TokenMetadata>().unwrap();
+    assert_eq!(metadata.symbol, "TEST");
+    assert_eq!(Option::<_>::from(metadata.update_authority), Some(pda::pool(&mint)));
+
+    let trader = ctx.new_user(5_000_000_000);
+    ctx.buy(&trader, &mint, 2_000_000_000).unwrap();
+    let bought = ctx.token_balance(&ctx.quote_token_ata(&trader.pubkey(), &mint));
+    assert!(bought > 0);
+    ctx.sell(&trader, &mint, bought).unwrap();
+    assert_eq!(ctx.token_balance(&ctx.quote_token_ata(&trader.pubkey(), &mint)), 0);
+
+    let curve: BondingCurve = ctx.fetch(&pda::bonding_curve(&mint));
+    let pool = pda::pool(&mint);
+    assert_eq!(ctx.token_balance(&ctx.quote_token_ata(&pool, &mint)), curve.quote_token_reserves);
+}
+
+#[test]
+fn fee_wallet_can_launch_a_token() {
+    let mut ctx = TestContext::bare();
+    let creator = ctx.new_user(10_000_000_000);
+    ctx.fee_wallet = creator.pubkey();
+    ctx.configure();
+
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    assert!(ctx.token_balance(&ctx.quote_token_ata(&creator.pubkey(), &mint)) > 0);
+}
+
+#[test]
+fn pda_mint_launch_needs_no_mint_signature() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = pda::mint(&creator.pubkey(), 7);
+    let mut params = ctx.create_pool_params(DEV_BUY);
+    params.mint_nonce = Some(7);
+
+    ctx.create_pool_with_params(&creator, None, &mint, params).unwrap();
+
+    let curve: BondingCurve = ctx.fetch(&pda::bonding_curve(&mint));
+    assert_eq!(curve.init_virtual_quote_token, INITIAL_VIRTUAL_QUOTE);
+    assert!(ctx.token_balance(&ctx.quote_token_ata(&creator.pubkey(), &mint)) > 0);
+}
+
+#[test]
+fn pda_mint_must_match_the_nonce() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = pda::mint(&creator.pubkey(), 7);
+    let mut params = ctx.create_pool_params(DEV_BUY);
+    params.mint_nonce = Some(8);
+
+    let result = ctx.create_pool_with_params(&creator, None, &mint, params);
+    assert_custom_error(result, CustomError::InvalidMintAddress);
+}
+
+#[test]
+fn launch_validates_token_metadata() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let cases = [
+        (" ", "TEST", "https://example.com/test.json", CustomError::EmptyTokenName),
+        ("Test Token", "TOOLONGSYMBOL", "https://example.com/test.json", CustomError::TokenSymbolTooLong),
+        ("Test Token", "TE-ST", "https://example.com/test.json", CustomError::InvalidTokenSymbol),
+        ("Test Token", "TEST", "http://example.com/test.json", CustomError::InvalidTokenUriScheme),
+    ];
+
+    for (name, symbol, uri, expected) in cases {
+        let mint = Keypair::new();
+        let mut params = ctx.create_pool_params(DEV_BUY);
+        params.name = name.to_string();
+        params.symbol = symbol.to_string();
+        params.uri = uri.to_string();
+        let result = ctx.create_pool_with_params(&creator, Some(&mint), &mint.pubkey(), params);
+        assert_custom_error(result, expected);
+    }
+}
+
+#[test]
+fn only_the_creator_updates_metadata() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let update = |signer: &Keypair, symbol: &str| {
+        instructions::update_token_metadata(
+            &signer.pubkey(),
+            &mint,
+            &anchor_spl::token::ID,
+            "Renamed".to_string(),
+            symbol.to_string(),
+            "https://example.com/renamed.json".to_string()
+        )
+    };
+
+    let intruder = ctx.new_user(0);
+    let result = ctx.send(&[update(&intruder, "RNM")], &[&intruder]);
+    assert_custom_error(result, CustomError::InvalidCreator);
+
+    let result = ctx.send(&[update(&creator, "R N M")], &[&creator]);
+    assert_custom_error(result, CustomError::InvalidTokenSymbol);
+
+    let outcome = ctx.send(&[update(&creator, "RNM")], &[&creator]).unwrap();
+    let updated = outcome.events.iter().find_map(|event| match event {
+        ProgramEvent::TokenMetadataUpdated(updated) => Some(updated),
+        _ => None,
+    });
+    let updated = updated.expect("no TokenMetadataUpdated emitted");
+    assert_eq!((updated.mint_address, updated.symbol.as_str()), (mint, "RNM"));
+    let account = ctx.svm.get_account(&pda::metadata(&mint)).unwrap();
+    let metadata = Metadata::safe_deserialize(&account.data).unwrap();
+    assert_eq!(metadata.symbol.trim_end_matches('\0'), "RNM");
+}
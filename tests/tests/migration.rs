@@ -0,0 +1,117 @@
+use pumpfun_client::{ pda, BondingCurve, Pool };
+use pumpfun_indexer::ProgramEvent;
+use pumpfun_smart_contract::errors::CustomError;
+use pumpfun_tests::*;
+use solana_sdk::{ pubkey::Pubkey, signature::Signer };
+
+const DEV_BUY: u64 = 1_000_000_000;
+
+fn cp_swap_pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &raydium_cp_swap::ID).0
+}
+
+/// Launches a token and buys it out, returns its mint
+fn completed_pool(ctx: &mut TestContext) -> Pubkey {
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let whale = ctx.new_user(200_000_000_000);
+    ctx.buy(&whale, &mint, BONDING_CURVE_LIMITATION).unwrap();
+    let curve: BondingCurve = ctx.fetch(&pda::bonding_curve(&mint));
+    assert!(curve.is_completed);
+    mint
+}
+
+#[test]
+fn migration_seeds_raydium_pool_with_curve_reserves() {
+    let mut ctx = TestContext::new();
+    let amm_config = ctx.load_cp_swap();
+    let mint = completed_pool(&mut ctx);
+    let pool_address = pda::pool(&mint);
+    let base_token_pool = ctx.base_token_ata(&pool_address);
+    let quote_token_pool = ctx.quote_token_ata(&pool_address, &mint);
+    let base_reserves = ctx.token_balance(&base_token_pool);
+    let quote_reserves = ctx.token_balance(&quote_token_pool);
+    let curve: BondingCurve = ctx.fetch(&pda::bonding_curve(&mint));
+    assert_eq!(base_reserves, curve.base_token_reserves);
+    assert_eq!(quote_reserves, curve.quote_token_reserves);
+
+    let keys = ctx.migrate_keys(&mint, &amm_config);
+    let admin = ctx.admin.insecure_clone();
+    let outcome = ctx.migrate(&keys, &admin).unwrap();
+
+    let (token_0_mint, token_1_mint) = if ctx.base_token_mint < mint {
+        (ctx.base_token_mint, mint)
+    } else {
+        (mint, ctx.base_token_mint)
+    };
+    let pool_state = cp_swap_pda(
+        &[b"pool", amm_config.as_ref(), token_0_mint.as_ref(), token_1_mint.as_ref()]
+    );
+    let vault = |token_mint: &Pubkey|
+        cp_swap_pda(&[b"pool_vault", pool_state.as_ref(), token_mint.as_ref()]);
+
+    assert_eq!(ctx.token_balance(&vault(&ctx.base_token_mint)), base_reserves);
+    assert_eq!(ctx.token_balance(&vault(&mint)), quote_reserves);
+    assert_eq!(ctx.token_balance(&base_token_pool), 0);
+    assert_eq!(ctx.token_balance(&quote_token_pool), 0);
+
+    let raydium_pool = ctx.svm.get_account(&pool_state).expect("Raydium pool was not created");
+    assert_eq!(raydium_pool.owner, raydium_cp_swap::ID);
+
+    let lp_mint = cp_swap_pda(&[b"pool_lp_mint", pool_state.as_ref()]);
+    let liquidity_owner = pda::liquidity_owner(&mint);
+    let owner_lp = pda::associated_token_address(&liquidity_owner, &lp_mint, &anchor_spl::token::ID);
+    assert!(ctx.token_balance(&owner_lp) > 0);
+
+    let pool: Pool = ctx.fetch(&pool_address);
+    assert_eq!(pool.migrated_pool, pool_state);
+    assert!(pool.migrated_at > 0);
+
+    let migration = outcome.events.iter().find_map(|event| match event {
+        ProgramEvent::Migration(migration) => Some(migration),
+        _ => None,
+    });
+    let migration = migration.expect("no MigrationEvent emitted");
+    assert_eq!(migration.mint_address, mint);
+    assert_eq!(migration.raydium_pool, pool_state);
+    assert_eq!(migration.contract, raydium_cp_swap::ID);
+}
+
+#[test]
+fn migration_requires_completed_curve() {
+    let mut ctx = TestContext::new();
+    let amm_config = ctx.load_cp_swap();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+
+    let keys = ctx.migrate_keys(&mint, &amm_config);
+    let admin = ctx.admin.insecure_clone();
+    let result = ctx.migrate(&keys, &admin);
+    assert_custom_error(result, CustomError::BondingCurveIsNotCompleted);
+}
+
+#[test]
+fn migration_requires_migration_authority() {
+    let mut ctx = TestContext::new();
+    let amm_config = ctx.load_cp_swap();
+    let mint = completed_pool(&mut ctx);
+    let intruder = ctx.new_user(0);
+
+    let mut keys = ctx.migrate_keys(&mint, &amm_config);
+    keys.migration_authority = intruder.pubkey();
+    let result = ctx.migrate(&keys, &intruder);
+    assert_custom_error(result, CustomError::InvalidMigrationAuth);
+}
+
+#[test]
+fn migration_runs_only_once() {
+    let mut ctx = TestContext::new();
+    let amm_config = ctx.load_cp_swap();
+    let mint = completed_pool(&mut ctx);
+    let keys = ctx.migrate_keys(&mint, &amm_config);
+    let admin = ctx.admin.insecure_clone();
+    ctx.migrate(&keys, &admin).unwrap();
+
+    let result = ctx.migrate(&keys, &admin);
+    assert_custom_error(result, CustomError::AlreadyMigrated);
+}
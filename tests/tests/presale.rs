@@ -0,0 +1,93 @@
+use pumpfun_client::{ pda, PresaleConfig, PresaleReceipt };
+use pumpfun_smart_contract::errors::CustomError;
+use pumpfun_tests::*;
+use solana_sdk::{ clock::Clock, keccak, pubkey::Pubkey, signature::{ Keypair, Signer } };
+
+const DEV_BUY: u64 = 1_000_000_000;
+const ALLOCATION: u64 = 2_000_000_000;
+const PRESALE_DURATION: i64 = 3600;
+
+fn leaf(user: &Pubkey, allocation: u64) -> [u8; 32] {
+    keccak::hashv(&[user.as_ref(), &allocation.to_le_bytes()]).to_bytes()
+}
+
+fn parent(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    keccak::hashv(&[&left, &right]).to_bytes()
+}
+
+struct Presale {
+    mint: Pubkey,
+    buyer: Keypair,
+    proof: Vec<[u8; 32]>,
+}
+
+/// Launches a token whose presale allowlists `buyer` and one other wallet
+fn launch_with_presale(ctx: &mut TestContext) -> Presale {
+    let buyer = ctx.new_user(5_000_000_000);
+    let other = leaf(&Pubkey::new_unique(), ALLOCATION);
+    let root = parent(leaf(&buyer.pubkey(), ALLOCATION), other);
+
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = Keypair::new();
+    let mut params = ctx.create_pool_params(DEV_BUY);
+    params.presale = Some(PresaleConfig {
+        merkle_root: root,
+        end_time: ctx.svm.get_sysvar::<Clock>().unix_timestamp + PRESALE_DURATION,
+    });
+    ctx.create_pool_with_params(&creator, Some(&mint), &mint.pubkey(), params).unwrap();
+
+    Presale { mint: mint.pubkey(), buyer, proof: vec![other] }
+}
+
+#[test]
+fn allowlisted_buyer_buys_up_to_its_allocation() {
+    let mut ctx = TestContext::new();
+    let Presale { mint, buyer, proof } = launch_with_presale(&mut ctx);
+
+    ctx.presale_buy(&buyer, &mint, ALLOCATION / 2, ALLOCATION, proof.clone()).unwrap();
+    ctx.presale_buy(&buyer, &mint, ALLOCATION / 2, ALLOCATION, proof.clone()).unwrap();
+
+    let receipt: PresaleReceipt = ctx.fetch(&pda::presale_receipt(&mint, &buyer.pubkey()));
+    assert_eq!(receipt.user, buyer.pubkey());
+    assert_eq!(receipt.purchased_amount, ALLOCATION);
+    assert!(ctx.token_balance(&ctx.quote_token_ata(&buyer.pubkey(), &mint)) > 0);
+
+    let result = ctx.presale_buy(&buyer, &mint, 1, ALLOCATION, proof);
+    assert_custom_error(result, CustomError::PresaleAllocationExceeded);
+}
+
+#[test]
+fn presale_rejects_wallets_outside_the_allowlist() {
+    let mut ctx = TestContext::new();
+    let Presale { mint, proof, .. } = launch_with_presale(&mut ctx);
+    let outsider = ctx.new_user(5_000_000_000);
+
+    let result = ctx.presale_buy(&outsider, &mint, ALLOCATION / 2, ALLOCATION, proof);
+    assert_custom_error(result, CustomError::InvalidPresaleProof);
+}
+
+#[test]
+fn presale_rejects_a_larger_claimed_allocation() {
+    let mut ctx = TestContext::new();
+    let Presale { mint, buyer, proof } = launch_with_presale(&mut ctx);
+
+    let result = ctx.presale_buy(&buyer, &mint, ALLOCATION, ALLOCATION * 2, proof);
+    assert_custom_error(result, CustomError::InvalidPresaleProof);
+}
+
+#[test]
+fn public_trading_opens_when_the_presale_ends() {
+    let mut ctx = TestContext::new();
+    let Presale { mint, buyer, proof } = launch_with_presale(&mut ctx);
+    let trader = ctx.new_user(5_000_000_000);
+
+    let result = ctx.buy(&trader, &mint, 1_000_000_000);
+    assert_custom_error(result, CustomError::PresaleIsActive);
+
+    ctx.warp_forward(PRESALE_DURATION);
+    ctx.buy(&trader, &mint, 1_000_000_000).unwrap();
+
+    let result = ctx.presale_buy(&buyer, &mint, ALLOCATION / 2, ALLOCATION, proof);
+    assert_custom_error(result, CustomError::PresaleIsNotActive);
+}
@@ -0,0 +1,119 @@
+use pumpfun_client::{ instructions, pda, quote, BaseTokenConfiguration, BondingCurve, SwapQuote };
+use pumpfun_smart_contract::errors::CustomError;
+use pumpfun_tests::*;
+use solana_sdk::{ pubkey::Pubkey, signature::Signer };
+
+const DEV_BUY: u64 = 1_000_000_000;
+
+fn curve(ctx: &TestContext, mint: &Pubkey) -> BondingCurve {
+    ctx.fetch(&pda::bonding_curve(mint))
+}
+
+fn base_config(ctx: &TestContext) -> BaseTokenConfiguration {
+    ctx.fetch(&pda::base_token_configuration(&ctx.base_token_mint))
+}
+
+#[test]
+fn quote_buy_matches_the_executed_buy() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(5_000_000_000);
+    let amount = 2_000_000_000;
+
+    let base_token_mint = ctx.base_token_mint;
+    let on_chain: SwapQuote = ctx.simulate_return(
+        instructions::quote_buy(&mint, &base_token_mint, amount)
+    );
+    let off_chain = quote::quote_buy(
+        &base_config(&ctx),
+        &curve(&ctx, &mint),
+        ctx.base_mint_fee().as_ref(),
+        amount
+    ).unwrap();
+    assert_eq!(on_chain, off_chain);
+
+    ctx.buy(&trader, &mint, amount).unwrap();
+    assert_eq!(
+        ctx.token_balance(&ctx.quote_token_ata(&trader.pubkey(), &mint)),
+        on_chain.amount_out
+    );
+    assert_eq!(curve(&ctx, &mint).price_x32().unwrap(), on_chain.new_price_x32);
+}
+
+#[test]
+fn quote_sell_matches_the_executed_sell() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(5_000_000_000);
+    ctx.buy(&trader, &mint, 2_000_000_000).unwrap();
+    let amount = ctx.token_balance(&ctx.quote_token_ata(&trader.pubkey(), &mint)) / 2;
+
+    let base_token_mint = ctx.base_token_mint;
+    let on_chain: SwapQuote = ctx.simulate_return(
+        instructions::quote_sell(&mint, &base_token_mint, amount)
+    );
+    let off_chain = quote::quote_sell(
+        &base_config(&ctx),
+        &curve(&ctx, &mint),
+        ctx.base_mint_fee().as_ref(),
+        amount
+    ).unwrap();
+    assert_eq!(on_chain, off_chain);
+
+    let before = ctx.token_balance(&ctx.base_token_ata(&trader.pubkey()));
+    ctx.sell(&trader, &mint, amount).unwrap();
+    assert_eq!(
+        ctx.token_balance(&ctx.base_token_ata(&trader.pubkey())) - before,
+        on_chain.amount_out
+    );
+}
+
+#[test]
+fn quote_rejects_another_base_token_configuration() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let other_base_token_mint = ctx.create_mint(BASE_DECIMALS);
+    ctx.add_base_token(&other_base_token_mint);
+
+    let result = ctx.send_as_admin(
+        instructions::quote_buy(&mint, &other_base_token_mint, 1_000_000_000)
+    );
+    assert_custom_error(result, CustomError::InvalidBaseToken);
+}
+
+#[test]
+fn twap_weights_prices_by_time() {
+    let mut ctx = TestContext::new();
+    // Observations with a zero timestamp are treated as unwritten
+    ctx.warp_forward(1_000);
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let launch_price = curve(&ctx, &mint).price_x32().unwrap();
+
+    ctx.warp_forward(10);
+    let trader = ctx.new_user(50_000_000_000);
+    ctx.buy(&trader, &mint, 20_000_000_000).unwrap();
+    let price = curve(&ctx, &mint).price_x32().unwrap();
+    ctx.warp_forward(10);
+
+    let twap: u128 = ctx.simulate_return(instructions::get_twap(&mint, 20));
+    assert_eq!(twap, (launch_price * 10 + price * 10) / 20);
+
+    let twap: u128 = ctx.simulate_return(instructions::get_twap(&mint, 5));
+    assert_eq!(twap, price);
+}
+
+#[test]
+fn twap_needs_observations_covering_the_window() {
+    let mut ctx = TestContext::new();
+    ctx.warp_forward(1_000);
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    ctx.warp_forward(10);
+
+    let result = ctx.send_as_admin(instructions::get_twap(&mint, 60));
+    assert_custom_error(result, CustomError::InsufficientObservations);
+}
@@ -0,0 +1,293 @@
+use pumpfun_client::{ instructions, pda, quote, BaseTokenConfiguration, BondingCurve, Pool };
+use pumpfun_indexer::ProgramEvent;
+use pumpfun_smart_contract::{ errors::CustomError, events::{ TradeOperation, TransactionEvent } };
+use pumpfun_tests::*;
+use solana_sdk::{ pubkey::Pubkey, signature::{ Keypair, Signer } };
+
+const DEV_BUY: u64 = 1_000_000_000;
+
+fn trade_event(outcome: &Outcome) -> &TransactionEvent {
+    outcome.events
+        .iter()
+        .find_map(|event| match event {
+            ProgramEvent::Trade(trade) => Some(trade),
+            _ => None,
+        })
+        .expect("no TransactionEvent emitted")
+}
+
+fn curve(ctx: &TestContext, mint: &Pubkey) -> BondingCurve {
+    ctx.fetch(&pda::bonding_curve(mint))
+}
+
+fn base_config(ctx: &TestContext) -> BaseTokenConfiguration {
+    ctx.fetch(&pda::base_token_configuration(&ctx.base_token_mint))
+}
+
+#[test]
+fn create_pool_initializes_curve_and_pool() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+
+    let mint_keypair = Keypair::new();
+    let mint = mint_keypair.pubkey();
+    let outcome = ctx.create_pool_with(&creator, &mint_keypair, DEV_BUY).unwrap();
+
+    let dev_buy_fee = (DEV_BUY * SWAP_FEE) / 10000;
+    let curve = curve(&ctx, &mint);
+    assert_eq!(curve.init_virtual_base_token, INITIAL_VIRTUAL_BASE);
+    assert_eq!(curve.init_virtual_quote_token, INITIAL_VIRTUAL_QUOTE);
+    assert_eq!(curve.k_value, (INITIAL_VIRTUAL_BASE as u128) * (INITIAL_VIRTUAL_QUOTE as u128));
+    assert_eq!(curve.base_token_reserves, DEV_BUY - dev_buy_fee);
+    assert!(!curve.is_completed);
+
+    let pool: Pool = ctx.fetch(&pda::pool(&mint));
+    assert_eq!(pool.creator, creator.pubkey());
+    assert_eq!(pool.mint_address, mint);
+    assert_eq!(pool.base_token_mint, ctx.base_token_mint);
+    assert_eq!(pool.trade_count, 1);
+
+    let event = trade_event(&outcome);
+    assert_eq!(event.operation, TradeOperation::DevBuy);
+    assert_eq!(event.sequence, 1);
+    assert_eq!(event.creator, creator.pubkey());
+    assert_eq!(event.input_amount, DEV_BUY);
+    assert_eq!(event.real_base_reserves, curve.base_token_reserves);
+    assert_eq!(event.virtual_quote_reserves, curve.quote_token_reserves);
+    assert_eq!(INITIAL_VIRTUAL_QUOTE - event.output_amount, curve.quote_token_reserves);
+
+    assert_eq!(
+        ctx.token_balance(&ctx.base_token_ata(&creator.pubkey())),
+        10_000_000_000 - DEV_BUY - CREATE_POOL_FEE
+    );
+    assert_eq!(
+        ctx.token_balance(&ctx.quote_token_ata(&creator.pubkey(), &mint)),
+        event.output_amount
+    );
+
+    let pool = pda::pool(&mint);
+    assert_eq!(ctx.token_balance(&ctx.base_token_ata(&pool)), curve.base_token_reserves);
+    assert_eq!(ctx.token_balance(&ctx.quote_token_ata(&pool, &mint)), curve.quote_token_reserves);
+    assert_eq!(event.real_quote_reserves, ctx.token_balance(&ctx.quote_token_ata(&pool, &mint)));
+}
+
+#[test]
+fn create_pool_rejects_fee_wallet_mismatch() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    ctx.fee_wallet = Pubkey::new_unique();
+
+    let result = ctx.create_pool_with(&creator, &Keypair::new(), DEV_BUY);
+    assert_custom_error(result, CustomError::InvalidFeeWallet);
+}
+
+#[test]
+fn buy_moves_tokens_and_updates_reserves() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(5_000_000_000);
+    let amount = 2_000_000_000;
+
+    let before = curve(&ctx, &mint);
+    let expected = quote::quote_buy(&base_config(&ctx), &before, ctx.base_mint_fee().as_ref(), amount).unwrap();
+    let protocol_fee_ata = pda::associated_token_address(
+        &pda::protocol_fee_vault(&ctx.base_token_mint),
+        &ctx.base_token_mint,
+        &anchor_spl::token::ID
+    );
+    let protocol_fees_before = ctx.token_balance(&protocol_fee_ata);
+
+    let outcome = ctx.buy(&trader, &mint, amount).unwrap();
+
+    let after = curve(&ctx, &mint);
+    assert_eq!(after.base_token_reserves, before.base_token_reserves + amount - expected.platform_fee);
+    assert_eq!(after.quote_token_reserves, before.quote_token_reserves - expected.amount_out);
+    assert_eq!(ctx.token_balance(&ctx.base_token_ata(&trader.pubkey())), 5_000_000_000 - amount);
+    assert_eq!(
+        ctx.token_balance(&ctx.quote_token_ata(&trader.pubkey(), &mint)),
+        expected.amount_out
+    );
+    assert_eq!(ctx.token_balance(&protocol_fee_ata) - protocol_fees_before, expected.platform_fee);
+
+    let event = trade_event(&outcome);
+    assert_eq!(event.operation, TradeOperation::Buy);
+    assert_eq!(event.sequence, 2);
+    assert_eq!(event.output_amount, expected.amount_out);
+    assert_eq!(event.platform_fee, expected.platform_fee);
+    assert_eq!(event.price_x32, expected.new_price_x32);
+    assert_eq!(event.real_base_reserves, after.base_token_reserves);
+
+    let pool: Pool = ctx.fetch(&pda::pool(&mint));
+    assert_eq!(pool.trade_count, 2);
+}
+
+#[test]
+fn buy_into_an_existing_token_account() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(5_000_000_000);
+    let trader_ata = ctx.create_quote_token_ata(&trader.pubkey(), &mint);
+
+    ctx.buy(&trader, &mint, 1_000_000_000).unwrap();
+    assert!(ctx.token_balance(&trader_ata) > 0);
+}
+
+#[test]
+fn pool_vaults_hold_the_reserves_after_trading() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(5_000_000_000);
+
+    ctx.buy(&trader, &mint, 3_000_000_000).unwrap();
+    let bought = ctx.token_balance(&ctx.quote_token_ata(&trader.pubkey(), &mint));
+    ctx.sell(&trader, &mint, bought / 3).unwrap();
+
+    let curve = curve(&ctx, &mint);
+    let pool = pda::pool(&mint);
+    assert_eq!(ctx.token_balance(&ctx.base_token_ata(&pool)), curve.base_token_reserves);
+    assert_eq!(ctx.token_balance(&ctx.quote_token_ata(&pool, &mint)), curve.quote_token_reserves);
+}
+
+#[test]
+fn sell_returns_base_token_minus_fee() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(5_000_000_000);
+    ctx.buy(&trader, &mint, 2_000_000_000).unwrap();
+
+    let quote_balance = ctx.token_balance(&ctx.quote_token_ata(&trader.pubkey(), &mint));
+    let base_balance = ctx.token_balance(&ctx.base_token_ata(&trader.pubkey()));
+    let sell_amount = quote_balance / 2;
+    let before = curve(&ctx, &mint);
+    let expected = quote::quote_sell(&base_config(&ctx), &before, ctx.base_mint_fee().as_ref(), sell_amount).unwrap();
+
+    let outcome = ctx.sell(&trader, &mint, sell_amount).unwrap();
+
+    let after = curve(&ctx, &mint);
+    let gross_out = expected.amount_out + expected.platform_fee;
+    assert_eq!(after.base_token_reserves, before.base_token_reserves - gross_out);
+    assert_eq!(after.quote_token_reserves, before.quote_token_reserves + sell_amount);
+    assert_eq!(
+        ctx.token_balance(&ctx.quote_token_ata(&trader.pubkey(), &mint)),
+        quote_balance - sell_amount
+    );
+    assert_eq!(
+        ctx.token_balance(&ctx.base_token_ata(&trader.pubkey())),
+        base_balance + expected.amount_out
+    );
+
+    let event = trade_event(&outcome);
+    assert_eq!(event.operation, TradeOperation::Sell);
+    assert_eq!(event.sequence, 3);
+    assert_eq!(event.input_amount, sell_amount);
+    assert_eq!(event.output_amount, expected.amount_out);
+    assert_eq!(event.platform_fee, expected.platform_fee);
+}
+
+#[test]
+fn trades_below_the_expected_amount_fail() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(5_000_000_000);
+    let keys = ctx.trade_keys(&trader.pubkey(), &mint);
+    let amount = 2_000_000_000;
+
+    let expected = quote::quote_buy(
+        &base_config(&ctx),
+        &curve(&ctx, &mint),
+        ctx.base_mint_fee().as_ref(),
+        amount
+    ).unwrap();
+    let result = ctx.send(&[instructions::buy(&keys, amount, expected.amount_out + 1)], &[&trader]);
+    assert_custom_error(result, CustomError::SlippageExceeded);
+    ctx.send(&[instructions::buy(&keys, amount, expected.amount_out)], &[&trader]).unwrap();
+
+    let bought = ctx.token_balance(&ctx.quote_token_ata(&trader.pubkey(), &mint));
+    let expected = quote::quote_sell(
+        &base_config(&ctx),
+        &curve(&ctx, &mint),
+        ctx.base_mint_fee().as_ref(),
+        bought
+    ).unwrap();
+    let result = ctx.send(&[instructions::sell(&keys, bought, expected.amount_out + 1)], &[&trader]);
+    assert_custom_error(result, CustomError::SlippageExceeded);
+    ctx.send(&[instructions::sell(&keys, bought, expected.amount_out)], &[&trader]).unwrap();
+}
+
+#[test]
+fn native_trades_unwrap_only_what_they_wrapped() {
+    let mut ctx = TestContext::native();
+    let creator = ctx.new_user(0);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let trader = ctx.new_user(0);
+    let wsol_ata = ctx.base_token_ata(&trader.pubkey());
+
+    // A wSOL account created for the trade is closed again
+    ctx.buy(&trader, &mint, 1_000_000_000).unwrap();
+    assert!(ctx.svm.get_account(&wsol_ata).is_none_or(|account| account.lamports == 0));
+
+    // wSOL the trader already held stays wrapped, along with the payout
+    let held = 500_000_000;
+    ctx.wrap_sol(&trader, held);
+    let bought = ctx.token_balance(&ctx.quote_token_ata(&trader.pubkey(), &mint));
+    let expected = quote::quote_sell(&base_config(&ctx), &curve(&ctx, &mint), None, bought).unwrap();
+    ctx.sell(&trader, &mint, bought).unwrap();
+    assert_eq!(ctx.token_balance(&wsol_ata), held + expected.amount_out);
+
+    ctx.buy(&trader, &mint, 100_000_000).unwrap();
+    assert_eq!(ctx.token_balance(&wsol_ata), held + expected.amount_out - 100_000_000);
+}
+
+#[test]
+fn buy_past_the_limitation_completes_the_curve() {
+    let mut ctx = TestContext::new();
+    let creator = ctx.new_user(10_000_000_000);
+    let mint = ctx.create_pool(&creator, DEV_BUY);
+    let whale = ctx.new_user(200_000_000_000);
+
+    let amount = BONDING_CURVE_LIMITATION;
+    let expected = quote::quote_buy(
+        &base_config(&ctx),
+        &curve(&ctx, &mint),
+        ctx.base_mint_fee().as_ref(),
+        amount
+    ).unwrap();
+    assert!(expected.completes_curve);
+
+    let outcome = ctx.buy(&whale, &mint, amount).unwrap();
+
+    assert!(curve(&ctx, &mint).is_completed);
+    let completed = outcome.events.iter().find_map(|event| match event {
+        ProgramEvent::BondingCurveCompleted(completed) => Some(completed),
+        _ => None,
+    });
+    assert_eq!(completed.expect("no BondingCurveCompleted emitted").mint_address, mint);
+
+    // Metadata is frozen for migration once the curve completes
+    let result = ctx.send(
+        &[
+            instructions::update_token_metadata(
+                &creator.pubkey(),
+                &mint,
+                &anchor_spl::token::ID,
+                "Renamed".to_string(),
+                "RNM".to_string(),
+                "https://example.com/renamed.json".to_string()
+            ),
+        ],
+        &[&creator]
+    );
+    assert_custom_error(result, CustomError::BondingCurveIsCompleted);
+
+    // The reserves stay put until they are migrated
+    let result = ctx.buy(&whale, &mint, 1_000_000_000);
+    assert_custom_error(result, CustomError::BondingCurveIsCompleted);
+    let bought = ctx.token_balance(&ctx.quote_token_ata(&whale.pubkey(), &mint));
+    let result = ctx.sell(&whale, &mint, bought);
+    assert_custom_error(result, CustomError::BondingCurveIsCompleted);
+}